@@ -18,9 +18,12 @@ use super::*;
 use crate::command::DOLPHIN_PARACHAIN_ID;
 
 use dolphin_runtime::{
-	AssetManagerConfig, CouncilConfig, DemocracyConfig, GenesisConfig, TechnicalCommitteeConfig,
+	AssetManagerConfig, CouncilConfig, DemocracyConfig, GenesisConfig, MantaPayConfig,
+	NativeAssetId, TechnicalCommitteeConfig,
 };
 use manta_primitives::helpers::{get_account_id_from_seed, get_collator_keys_from_seed};
+use pallet_manta_pay::types::ShieldingPolicy;
+use sp_core::Get;
 
 /// Specialized `ChainSpec` for the normal parachain runtime.
 pub type DolphinChainSpec =
@@ -206,6 +209,10 @@ fn dolphin_dev_genesis(
 			phantom: Default::default(),
 		},
 		asset_manager: Default::default(),
+		manta_pay: MantaPayConfig {
+			shielding_policies: vec![(NativeAssetId::get(), ShieldingPolicy::unrestricted())],
+			..Default::default()
+		},
 		council_membership: Default::default(),
 		technical_membership: Default::default(),
 		aura_ext: Default::default(),
@@ -15,6 +15,7 @@
 // along with Manta.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::chain_specs;
+use manta_primitives::types::Hash;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
@@ -44,6 +45,10 @@ pub enum Subcommand {
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
+	/// Export the MantaPay ledger of a given block in the format of its genesis config.
+	#[structopt(name = "export-manta-pay-state")]
+	ExportMantaPayState(ExportMantaPayStateCommand),
+
 	/// Remove the whole chain.
 	PurgeChain(cumulus_client_cli::PurgeChainCmd),
 
@@ -102,6 +107,32 @@ pub struct ExportGenesisWasmCommand {
 	pub chain: Option<String>,
 }
 
+/// Command for exporting the MantaPay ledger of a Dolphin chain.
+///
+/// The ledger is paged out of the `mantaPay_pullLedgerDiff` RPC of a running node, so the node
+/// does not have to be stopped.
+#[derive(Debug, StructOpt)]
+pub struct ExportMantaPayStateCommand {
+	/// HTTP RPC endpoint of the node to export the ledger from.
+	#[structopt(long, default_value = "http://localhost:9933")]
+	pub rpc_url: String,
+
+	/// Block hash to export the ledger at. Defaults to the finalized head of the node.
+	#[structopt(long)]
+	pub at: Option<Hash>,
+
+	/// Output file name or stdout if unspecified.
+	#[structopt(long, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	/// Plain chain spec to seed with the exported ledger.
+	///
+	/// If specified, the chain spec is written out with the exported UTXOs, void numbers and
+	/// asset balances in its MantaPay genesis config, instead of the bare ledger.
+	#[structopt(long, parse(from_os_str))]
+	pub into: Option<PathBuf>,
+}
+
 #[derive(Debug, StructOpt)]
 #[structopt(settings = &[
 	structopt::clap::AppSettings::GlobalVersion,
@@ -115,6 +146,12 @@ pub struct Cli {
 	#[structopt(flatten)]
 	pub run: cumulus_client_cli::RunCmd,
 
+	/// Index the full MantaPay shard trees and serve `mantaPay_getMembershipPath`.
+	///
+	/// Only available on dolphin chains.
+	#[structopt(long)]
+	pub manta_pay_index: bool,
+
 	/// Relaychain arguments
 	#[structopt(raw = true)]
 	pub relaychain_args: Vec<String>,
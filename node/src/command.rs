@@ -16,24 +16,36 @@
 
 use crate::{
 	chain_specs,
-	cli::{Cli, RelayChainCli, Subcommand},
+	cli::{Cli, ExportMantaPayStateCommand, RelayChainCli, Subcommand},
 	service::{new_partial, CalamariRuntimeExecutor, DolphinRuntimeExecutor, MantaRuntimeExecutor},
 };
 
-use codec::Encode;
+use codec::{Decode, Encode};
 use cumulus_client_service::genesis::generate_genesis_block;
 use cumulus_primitives_core::ParaId;
+use jsonrpc_core_client::{transports::http, RpcError};
 use log::info;
 
-use manta_primitives::types::{AuraId, Header};
+use manta_crypto::merkle_tree::forest::Configuration as _;
+use manta_pay::config;
+use manta_primitives::types::{AssetId, AuraId, Balance, BlockNumber, Hash, Header};
+use pallet_manta_pay::{
+	rpc::gen_client::Client as PullClient,
+	types::{Checkpoint, Utxo},
+};
 use polkadot_parachain::primitives::AccountIdConversion;
 use sc_cli::{
 	ChainSpec, CliConfiguration, DefaultConfigurationValues, ImportParams, KeystoreParams,
 	NetworkParams, Result, RuntimeVersion, SharedParams, SubstrateCli,
 };
+use sc_rpc_api::{chain::ChainClient, state::StateClient};
 use sc_service::config::{BasePath, PrometheusConfig};
-use sp_core::hexdisplay::HexDisplay;
-use sp_runtime::{generic, traits::Block as BlockT, OpaqueExtrinsic};
+use sp_core::{hexdisplay::HexDisplay, storage::StorageKey, twox_128};
+use sp_runtime::{
+	generic::{self, SignedBlock},
+	traits::Block as BlockT,
+	OpaqueExtrinsic,
+};
 use std::{io::Write, net::SocketAddr};
 
 pub type Block = generic::Block<Header, OpaqueExtrinsic>;
@@ -208,6 +220,123 @@ fn extract_genesis_wasm(chain_spec: &Box<dyn sc_service::ChainSpec>) -> Result<V
 		.ok_or_else(|| "Could not find wasm file in genesis state!".into())
 }
 
+/// Number of `ShieldedDeposits` keys requested per `state_getKeysPaged` call.
+const SHIELDED_DEPOSITS_PAGE_SIZE: u32 = 1000;
+
+/// Returns the shard index of `utxo`.
+fn shard_index(utxo: &Utxo) -> Result<u8> {
+	let utxo = <config::Utxo as Decode>::decode(&mut utxo.as_slice())
+		.map_err(|_| "The node returned an undecodable UTXO.")?;
+	Ok(config::MerkleTreeConfiguration::tree_index(&utxo))
+}
+
+/// Exports the MantaPay ledger of the node at `cmd.rpc_url` in the format of the MantaPay genesis
+/// config.
+async fn export_manta_pay_state(cmd: &ExportMantaPayStateCommand) -> Result<()> {
+	let rpc_error = |err: RpcError| format!("MantaPay ledger export failed: {}", err);
+	let chain =
+		http::connect::<ChainClient<BlockNumber, Hash, Header, SignedBlock<Block>>>(&cmd.rpc_url)
+			.await
+			.map_err(rpc_error)?;
+	let state = http::connect::<StateClient<Hash>>(&cmd.rpc_url)
+		.await
+		.map_err(rpc_error)?;
+	let pull = http::connect::<PullClient<Hash>>(&cmd.rpc_url)
+		.await
+		.map_err(rpc_error)?;
+	let at = match cmd.at {
+		Some(at) => at,
+		_ => chain.finalized_head().await.map_err(rpc_error)?,
+	};
+	let mut checkpoint = Checkpoint::default();
+	let mut utxos = Vec::new();
+	let mut void_numbers = Vec::new();
+	loop {
+		let response = pull
+			.pull_ledger_diff(checkpoint.clone(), Some(at))
+			.await
+			.map_err(rpc_error)?;
+		for (utxo, note) in response.receivers {
+			let shard_index = shard_index(&utxo)?;
+			let receiver_index = &mut checkpoint.receiver_index;
+			if receiver_index.len() <= shard_index as usize {
+				receiver_index.resize(shard_index as usize + 1, 0);
+			}
+			receiver_index[shard_index as usize] += 1;
+			utxos.push((shard_index, utxo, note));
+		}
+		checkpoint.sender_index += response.senders.len() as u64;
+		void_numbers.extend(response.senders);
+		if !response.should_continue {
+			break;
+		}
+	}
+	// Pages are ordered by shard, so a stable sort keeps the leaf order within each shard.
+	utxos.sort_by_key(|(shard_index, _, _)| *shard_index);
+	let prefix = [twox_128(b"MantaPay"), twox_128(b"ShieldedDeposits")].concat();
+	let mut keys = Vec::new();
+	loop {
+		let page = state
+			.storage_keys_paged(
+				Some(StorageKey(prefix.clone())),
+				SHIELDED_DEPOSITS_PAGE_SIZE,
+				keys.last().cloned(),
+				Some(at),
+			)
+			.await
+			.map_err(rpc_error)?;
+		let is_last = page.len() < SHIELDED_DEPOSITS_PAGE_SIZE as usize;
+		keys.extend(page);
+		if is_last {
+			break;
+		}
+	}
+	let mut balances = Vec::new();
+	for key in keys {
+		// Keys are hashed with `Twox64Concat`, so the asset id follows the 8-byte hash.
+		let asset_id = AssetId::decode(&mut key.0.get(prefix.len() + 8..).unwrap_or_default())?;
+		if let Some(value) = state.storage(key, Some(at)).await.map_err(rpc_error)? {
+			balances.push((asset_id, Balance::decode(&mut value.0.as_slice())?));
+		}
+	}
+	balances.sort_by_key(|(asset_id, _)| *asset_id);
+	info!(
+		"Exporting {} UTXOs, {} void numbers and {} asset balances at {:?}",
+		utxos.len(),
+		void_numbers.len(),
+		balances.len(),
+		at,
+	);
+	let ledger = serde_json::json!({
+		"utxos": utxos,
+		"voidNumbers": void_numbers,
+		"balances": balances,
+	});
+	let output = match &cmd.into {
+		Some(path) => {
+			let mut spec = serde_json::from_slice::<serde_json::Value>(&std::fs::read(path)?)
+				.map_err(|err| format!("Unable to parse the chain spec: {}", err))?;
+			let manta_pay = spec
+				.pointer_mut("/genesis/runtime/mantaPay")
+				.and_then(serde_json::Value::as_object_mut)
+				.ok_or("The chain spec must be a plain Dolphin chain spec.")?;
+			manta_pay.insert("utxos".into(), ledger["utxos"].clone());
+			manta_pay.insert("voidNumbers".into(), ledger["voidNumbers"].clone());
+			manta_pay.insert("balances".into(), ledger["balances"].clone());
+			spec
+		}
+		_ => ledger,
+	};
+	let output = serde_json::to_vec_pretty(&output)
+		.map_err(|err| format!("Unable to serialize the MantaPay ledger: {}", err))?;
+	if let Some(path) = &cmd.output {
+		std::fs::write(path, output)?;
+	} else {
+		std::io::stdout().write_all(&output)?;
+	}
+	Ok(())
+}
+
 macro_rules! construct_async_run {
 	(|$components:ident, $cli:ident, $cmd:ident, $config:ident| $( $code:tt )* ) => {{
 		let runner = $cli.create_runner($cmd)?;
@@ -273,6 +402,13 @@ pub fn run() -> Result<()> {
 				Ok(cmd.run(components.client, components.import_queue))
 			})
 		}
+		Some(Subcommand::ExportMantaPayState(cmd)) => {
+			let mut builder = sc_cli::LoggerBuilder::new("");
+			builder.with_profiling(sc_tracing::TracingReceiver::Log, "");
+			let _ = builder.init();
+
+			tokio::runtime::Runtime::new()?.block_on(export_manta_pay_state(cmd))
+		}
 		Some(Subcommand::PurgeChain(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 
@@ -380,6 +516,13 @@ pub fn run() -> Result<()> {
 						task_manager,
 					))
 				})
+			} else if runner.config().chain_spec.is_dolphin() {
+				runner.async_run(|config| {
+					Ok((
+						cmd.run::<Block, DolphinRuntimeExecutor>(config),
+						task_manager,
+					))
+				})
 			} else {
 				Err("Chain doesn't support try-runtime".into())
 			}
@@ -403,6 +546,10 @@ pub fn run() -> Result<()> {
 						.chain(cli.relaychain_args.iter()),
 				);
 
+				if cli.manta_pay_index && !config.chain_spec.is_dolphin() {
+					return Err("The MantaPay index is only available on dolphin chains.".into());
+				}
+
 				let id = ParaId::from(para_id);
 
 				let parachain_account =
@@ -438,7 +585,14 @@ pub fn run() -> Result<()> {
 						manta_runtime::RuntimeApi,
 						MantaRuntimeExecutor,
 						AuraId,
-					>(config, polkadot_config, id)
+						_,
+					>(
+						config,
+						polkadot_config,
+						id,
+						false,
+						crate::rpc::create_full,
+					)
 					.await
 					.map(|r| r.0)
 					.map_err(Into::into)
@@ -447,7 +601,14 @@ pub fn run() -> Result<()> {
 						calamari_runtime::RuntimeApi,
 						CalamariRuntimeExecutor,
 						AuraId,
-					>(config, polkadot_config, id)
+						_,
+					>(
+						config,
+						polkadot_config,
+						id,
+						false,
+						crate::rpc::create_full,
+					)
 					.await
 					.map(|r| r.0)
 					.map_err(Into::into)
@@ -456,7 +617,14 @@ pub fn run() -> Result<()> {
 						dolphin_runtime::RuntimeApi,
 						DolphinRuntimeExecutor,
 						AuraId,
-					>(config, polkadot_config, id)
+						_,
+					>(
+						config,
+						polkadot_config,
+						id,
+						cli.manta_pay_index,
+						crate::rpc::create_dolphin_full,
+					)
 					.await
 					.map(|r| r.0)
 					.map_err(Into::into)
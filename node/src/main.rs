@@ -20,6 +20,7 @@
 mod chain_specs;
 mod cli;
 mod command;
+mod manta_pay_index;
 mod rpc;
 mod service;
 
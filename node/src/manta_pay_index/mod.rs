@@ -0,0 +1,275 @@
+// Copyright 2020-2022 Manta Network.
+// This file is part of Manta.
+//
+// Manta is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Manta is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Manta.  If not, see <http://www.gnu.org/licenses/>.
+
+//! MantaPay shard index service.
+//!
+//! Follows the best chain and keeps the full tree of every MantaPay shard in a
+//! [`ShardIndex`], so that the node can answer `mantaPay_getMembershipPath`. Indexed UTXOs and
+//! the shard lengths at the last finalized block are persisted in the aux store. When the best
+//! chain does not extend the indexed chain, the index rolls back to the finalized shard lengths
+//! and indexes the new best block from there.
+
+use codec::{Decode, Encode};
+use futures::{future::Either, stream, StreamExt};
+use log::{info, warn};
+use manta_primitives::types::{Block, Hash};
+use pallet_manta_pay::types::{EncryptedNote, Utxo, VersionedParameters, SHARD_COUNT};
+use sc_client_api::{AuxStore, Backend, BlockchainEvents, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{storage::StorageKey, twox_128};
+use sp_runtime::{generic::BlockId, traits::Header as HeaderT};
+use std::sync::{Arc, RwLock};
+
+mod shard_index;
+
+pub use shard_index::{ShardIndex, SharedShardIndex};
+
+/// Aux store key of the finalized index state.
+const FINALIZED_KEY: &[u8] = b"manta_pay_index_finalized";
+
+/// Aux store key prefix of the indexed UTXOs.
+const LEAF_KEY_PREFIX: &[u8] = b"manta_pay_index_leaf";
+
+/// Shard lengths of an indexed block.
+#[derive(Clone, Debug, Decode, Encode)]
+struct IndexedBlock {
+	/// Block hash
+	hash: Hash,
+	/// Number of UTXOs in each shard at the end of the block
+	lengths: Vec<u64>,
+}
+
+/// Returns the storage key of the MantaPay storage item `name` at `key`.
+pub(crate) fn storage_key(name: &[u8], key: &[u8]) -> StorageKey {
+	StorageKey([&twox_128(b"MantaPay")[..], &twox_128(name), key].concat())
+}
+
+/// Returns the aux store key of the UTXO at `leaf_index` in the shard at `shard_index`.
+fn leaf_key(shard_index: u8, leaf_index: u64) -> Vec<u8> {
+	[LEAF_KEY_PREFIX, &(shard_index, leaf_index).encode()].concat()
+}
+
+/// Builds a new shared shard index, which is filled in by [`run`].
+pub fn new_shared_index() -> SharedShardIndex {
+	Arc::new(RwLock::new(None))
+}
+
+/// Shard index worker.
+struct Worker<C, BE> {
+	/// Client
+	client: Arc<C>,
+	/// Shared shard index
+	index: SharedShardIndex,
+	/// Last indexed finalized block
+	finalized: IndexedBlock,
+	/// Indexed blocks on top of `finalized`, ending with the indexed best block
+	chain: Vec<IndexedBlock>,
+	/// Backend type marker
+	__: std::marker::PhantomData<BE>,
+}
+
+impl<C, BE> Worker<C, BE>
+where
+	BE: Backend<Block>,
+	C: AuxStore + HeaderBackend<Block> + StorageProvider<Block, BE>,
+{
+	/// Returns the last indexed block.
+	fn head(&self) -> &IndexedBlock {
+		self.chain.last().unwrap_or(&self.finalized)
+	}
+
+	/// Builds an empty shard index with the UTXO accumulator model registered at `hash`.
+	fn empty_index(&self, hash: Hash) -> Result<ShardIndex, String> {
+		let model = self
+			.client
+			.storage(
+				&BlockId::Hash(hash),
+				&storage_key(b"UtxoAccumulatorModelParameters", &[]),
+			)
+			.map_err(|err| err.to_string())?
+			.map(|data| VersionedParameters::decode(&mut data.0.as_slice()))
+			.transpose()
+			.map_err(|err| err.to_string())?;
+		ShardIndex::new(model.as_ref().map(|parameters| parameters.bytes.as_slice()))
+			.ok_or_else(|| "Unable to decode the UTXO accumulator model.".to_string())
+	}
+
+	/// Loads the finalized index state from the aux store, or indexes the finalized block if
+	/// nothing has been persisted yet.
+	fn load(client: Arc<C>, index: SharedShardIndex) -> Result<Self, String> {
+		let persisted = client
+			.get_aux(FINALIZED_KEY)
+			.map_err(|err| err.to_string())?
+			.map(|data| IndexedBlock::decode(&mut data.as_slice()))
+			.transpose()
+			.map_err(|err| err.to_string())?;
+		let mut worker = Self {
+			finalized: IndexedBlock {
+				hash: client.info().finalized_hash,
+				lengths: vec![0; SHARD_COUNT],
+			},
+			client,
+			index,
+			chain: Vec::new(),
+			__: Default::default(),
+		};
+		match persisted {
+			Some(finalized) => {
+				let mut shard_index = worker.empty_index(finalized.hash)?;
+				for (shard, length) in finalized.lengths.iter().enumerate() {
+					for leaf_index in 0..*length {
+						let utxo = worker
+							.client
+							.get_aux(&leaf_key(shard as u8, leaf_index))
+							.map_err(|err| err.to_string())?
+							.map(|data| Utxo::decode(&mut data.as_slice()))
+							.transpose()
+							.map_err(|err| err.to_string())?
+							.ok_or("Missing indexed UTXO in the aux store.")?;
+						shard_index
+							.push(shard as u8, utxo)
+							.map_err(|err| format!("{:?}", err))?;
+					}
+				}
+				worker.finalized = finalized;
+				*worker.index.write().expect("Shard index lock is poisoned.") = Some(shard_index);
+			}
+			_ => {
+				let hash = worker.finalized.hash;
+				worker.finalized.hash = Default::default();
+				worker.index_block(hash)?;
+				worker.finalize(hash)?;
+			}
+		}
+		Ok(worker)
+	}
+
+	/// Indexes the UTXOs added by the block with the given `hash`, rolling back to the finalized
+	/// state first if the block does not extend the indexed chain.
+	fn index_block(&mut self, hash: Hash) -> Result<(), String> {
+		let header = self
+			.client
+			.header(BlockId::Hash(hash))
+			.map_err(|err| err.to_string())?
+			.ok_or("Unknown block.")?;
+		if self.head().hash == hash {
+			return Ok(());
+		}
+		if *header.parent_hash() != self.head().hash {
+			self.chain.clear();
+		}
+		let mut lengths = self.head().lengths.clone();
+		let mut leaves = Vec::new();
+		for (shard, length) in lengths.iter_mut().enumerate() {
+			while let Some(data) = self
+				.client
+				.storage(
+					&BlockId::Hash(hash),
+					&storage_key(b"Shards", &(shard as u8, *length).encode()),
+				)
+				.map_err(|err| err.to_string())?
+			{
+				let (utxo, _) = <(Utxo, EncryptedNote)>::decode(&mut data.0.as_slice())
+					.map_err(|err| err.to_string())?;
+				leaves.push((shard as u8, *length, utxo));
+				*length += 1;
+			}
+		}
+		let mut index = self.index.write().expect("Shard index lock is poisoned.");
+		if let Some(index) = index.as_mut() {
+			index.truncate(&self.head().lengths);
+		}
+		if index.as_ref().map_or(true, ShardIndex::is_empty) {
+			*index = Some(self.empty_index(hash)?);
+		}
+		let index = index.as_mut().expect("The shard index was set above.");
+		for (shard_index, _, utxo) in &leaves {
+			if let Err(err) = index.push(*shard_index, *utxo) {
+				index.truncate(&self.head().lengths);
+				return Err(format!("{:?}", err));
+			}
+		}
+		let leaves = leaves
+			.into_iter()
+			.map(|(shard_index, leaf_index, utxo)| (leaf_key(shard_index, leaf_index), utxo))
+			.collect::<Vec<_>>();
+		self.client
+			.insert_aux(
+				&leaves
+					.iter()
+					.map(|(key, utxo)| (key.as_slice(), utxo.as_slice()))
+					.collect::<Vec<_>>(),
+				&[],
+			)
+			.map_err(|err| err.to_string())?;
+		self.chain.push(IndexedBlock { hash, lengths });
+		Ok(())
+	}
+
+	/// Persists the indexed state of the finalized block with the given `hash`, if it has been
+	/// indexed.
+	fn finalize(&mut self, hash: Hash) -> Result<(), String> {
+		if let Some(position) = self.chain.iter().position(|block| block.hash == hash) {
+			self.finalized = self
+				.chain
+				.drain(..=position)
+				.last()
+				.expect("Position is in range.");
+			self.client
+				.insert_aux(&[(FINALIZED_KEY, self.finalized.encode().as_slice())], &[])
+				.map_err(|err| err.to_string())?;
+		}
+		Ok(())
+	}
+}
+
+/// Runs the shard index service, filling in `index` and keeping it up to date with the best
+/// block of `client`.
+///
+/// Loading the index, reading block storage and rebuilding shard trees on reorgs are all
+/// synchronous, so this future must be spawned as a blocking task.
+pub async fn run<C, BE>(client: Arc<C>, index: SharedShardIndex)
+where
+	BE: Backend<Block>,
+	C: AuxStore + BlockchainEvents<Block> + HeaderBackend<Block> + StorageProvider<Block, BE>,
+{
+	let imports = client.import_notification_stream();
+	let finality = client.finality_notification_stream();
+	let mut worker = match Worker::load(client.clone(), index) {
+		Ok(worker) => worker,
+		Err(err) => {
+			warn!("Unable to load the MantaPay shard index: {}", err);
+			return;
+		}
+	};
+	info!("MantaPay shard index loaded at {}", worker.finalized.hash);
+	if let Err(err) = worker.index_block(client.info().best_hash) {
+		warn!("Unable to index the best block: {}", err);
+	}
+	let mut notifications = stream::select(imports.map(Either::Left), finality.map(Either::Right));
+	while let Some(notification) = notifications.next().await {
+		let result = match notification {
+			Either::Left(notification) if notification.is_new_best => {
+				worker.index_block(notification.hash)
+			}
+			Either::Right(notification) => worker.finalize(notification.hash),
+			_ => Ok(()),
+		};
+		if let Err(err) = result {
+			warn!("Unable to update the MantaPay shard index: {}", err);
+		}
+	}
+}
@@ -0,0 +1,231 @@
+// Copyright 2020-2022 Manta Network.
+// This file is part of Manta.
+//
+// Manta is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Manta is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Manta.  If not, see <http://www.gnu.org/licenses/>.
+
+//! MantaPay Shard Index
+//!
+//! The pallet only stores the current path of each shard tree, so wallets would otherwise need
+//! to download every UTXO and rebuild the whole forest to spend their assets. The [`ShardIndex`]
+//! keeps the full tree of every shard so that a node can serve membership paths instead.
+
+use codec::{Decode, Encode};
+use manta_crypto::merkle_tree::{forest::Configuration as _, full::Full, MerkleTree};
+use manta_pay::config;
+use manta_util::{codec::Decode as ModelDecode, into_array_unchecked};
+use pallet_manta_pay::types::{MembershipPath, Utxo, SHARD_COUNT};
+use std::{
+	collections::HashMap,
+	sync::{Arc, RwLock},
+};
+
+/// Full Shard Tree
+type ShardTree = MerkleTree<config::MerkleTreeConfiguration, Full<config::MerkleTreeConfiguration>>;
+
+/// Encodes the SCALE encodable `value` into a 32-byte array.
+#[inline]
+fn encode<T>(value: T) -> [u8; 32]
+where
+	T: Encode,
+{
+	into_array_unchecked(value.encode())
+}
+
+/// Decodes `utxo` into a leaf of the shard trees.
+#[inline]
+fn decode(utxo: Utxo) -> Option<config::Utxo> {
+	<config::Utxo as Decode>::decode(&mut utxo.as_slice()).ok()
+}
+
+/// Shared Shard Index
+///
+/// The index is `None` until the node has loaded it.
+pub type SharedShardIndex = Arc<RwLock<Option<ShardIndex>>>;
+
+/// Shard Index Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ShardIndexError {
+	/// Invalid UTXO
+	///
+	/// The UTXO could not be decoded.
+	InvalidUtxo,
+
+	/// Wrong Shard
+	///
+	/// The UTXO does not belong to the shard it was pushed to.
+	WrongShard,
+
+	/// Shard Full
+	///
+	/// The shard tree has no room left for the UTXO.
+	ShardFull,
+}
+
+/// Shard Index
+///
+/// Full Merkle trees of every UTXO shard along with the position of each UTXO.
+pub struct ShardIndex {
+	/// UTXO Accumulator Model
+	model: config::UtxoAccumulatorModel,
+
+	/// Shard Trees
+	trees: Vec<ShardTree>,
+
+	/// Shard Leaves
+	leaves: Vec<Vec<Utxo>>,
+
+	/// UTXO Positions
+	positions: HashMap<Utxo, (u8, u64)>,
+}
+
+impl ShardIndex {
+	/// Builds a new empty [`ShardIndex`] from the serialized UTXO accumulator `model`, falling
+	/// back to the built-in testnet model if `model` is `None`. Returns `None` if the model
+	/// cannot be decoded.
+	#[inline]
+	pub fn new(model: Option<&[u8]>) -> Option<Self> {
+		let model = match model {
+			Some(bytes) => ModelDecode::decode(bytes).ok()?,
+			_ => ModelDecode::decode(
+				manta_sdk::pay::testnet::parameters::UtxoAccumulatorModel::get()?,
+			)
+			.ok()?,
+		};
+		Some(Self {
+			trees: (0..SHARD_COUNT)
+				.map(|_| ShardTree::new(model.clone()))
+				.collect(),
+			leaves: (0..SHARD_COUNT).map(|_| Vec::new()).collect(),
+			positions: Default::default(),
+			model,
+		})
+	}
+
+	/// Returns `true` if no UTXO has been indexed.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.positions.is_empty()
+	}
+
+	/// Returns the number of UTXOs indexed in each shard.
+	#[inline]
+	pub fn lengths(&self) -> Vec<u64> {
+		self.leaves
+			.iter()
+			.map(|leaves| leaves.len() as u64)
+			.collect()
+	}
+
+	/// Appends `utxo` to the shard at `shard_index`.
+	#[inline]
+	pub fn push(&mut self, shard_index: u8, utxo: Utxo) -> Result<(), ShardIndexError> {
+		let leaf = decode(utxo).ok_or(ShardIndexError::InvalidUtxo)?;
+		if config::MerkleTreeConfiguration::tree_index(&leaf) != shard_index {
+			return Err(ShardIndexError::WrongShard);
+		}
+		let shard = shard_index as usize;
+		if !self.trees[shard].push(&leaf) {
+			return Err(ShardIndexError::ShardFull);
+		}
+		self.positions
+			.insert(utxo, (shard_index, self.leaves[shard].len() as u64));
+		self.leaves[shard].push(utxo);
+		Ok(())
+	}
+
+	/// Truncates every shard to the length given in `lengths`, rebuilding the trees of the shards
+	/// which shrink. Shards without an entry in `lengths` are emptied.
+	#[inline]
+	pub fn truncate(&mut self, lengths: &[u64]) {
+		for shard in 0..SHARD_COUNT {
+			let length = lengths.get(shard).copied().unwrap_or_default() as usize;
+			if self.leaves[shard].len() <= length {
+				continue;
+			}
+			for utxo in self.leaves[shard].drain(length..) {
+				self.positions.remove(&utxo);
+			}
+			let mut tree = ShardTree::new(self.model.clone());
+			for utxo in &self.leaves[shard] {
+				let leaf = decode(*utxo).expect("Indexed UTXOs have already been decoded once.");
+				tree.push(&leaf);
+			}
+			self.trees[shard] = tree;
+		}
+	}
+
+	/// Returns the membership path of `utxo` in its shard tree, or `None` if `utxo` has not been
+	/// indexed.
+	#[inline]
+	pub fn membership_path(&self, utxo: &Utxo) -> Option<MembershipPath> {
+		let (shard_index, leaf_index) = *self.positions.get(utxo)?;
+		let tree = &self.trees[shard_index as usize];
+		let path = tree.path(leaf_index as usize).ok()?;
+		Some(MembershipPath {
+			shard_index,
+			leaf_index,
+			root: encode(tree.root()),
+			sibling_digest: encode(path.sibling_digest),
+			inner_path: path.inner_path.path.into_iter().map(encode).collect(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// Returns the `n`th test UTXO along with its shard.
+	fn utxo(n: u64) -> (u8, Utxo) {
+		let mut utxo = [0; 32];
+		utxo[..8].copy_from_slice(&n.to_le_bytes());
+		let leaf = decode(utxo).expect("Small integers are valid UTXOs.");
+		(config::MerkleTreeConfiguration::tree_index(&leaf), utxo)
+	}
+
+	/// Tests that truncating the index restores the membership paths it served before the
+	/// truncated UTXOs were pushed.
+	#[test]
+	fn truncate_should_restore_membership_paths() {
+		let mut index = ShardIndex::new(None).expect("The testnet model should decode.");
+		let utxos = (1..=32).map(utxo).collect::<Vec<_>>();
+		for (shard_index, utxo) in &utxos[..16] {
+			assert_eq!(index.push(*shard_index, *utxo), Ok(()));
+		}
+		let lengths = index.lengths();
+		let paths = utxos[..16]
+			.iter()
+			.map(|(_, utxo)| index.membership_path(utxo))
+			.collect::<Vec<_>>();
+		for (shard_index, utxo) in &utxos[16..] {
+			assert_eq!(index.push(*shard_index, *utxo), Ok(()));
+		}
+		let (shard_index, utxo) = utxo(33);
+		assert_eq!(
+			index.push(shard_index.wrapping_add(1), utxo),
+			Err(ShardIndexError::WrongShard)
+		);
+		index.truncate(&lengths);
+		assert_eq!(index.lengths(), lengths);
+		for ((_, utxo), path) in utxos[..16].iter().zip(paths) {
+			assert!(path.is_some());
+			assert_eq!(index.membership_path(utxo), path);
+		}
+		assert!(utxos[16..]
+			.iter()
+			.all(|(_, utxo)| index.membership_path(utxo).is_none()));
+		index.truncate(&[]);
+		assert!(index.is_empty());
+	}
+}
@@ -16,16 +16,37 @@
 
 //! Parachain-specific RPCs implementation.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use sc_client_api::AuxStore;
+use codec::{Decode, Encode};
+use futures::{
+	channel::mpsc::{self, UnboundedSender},
+	future::Either,
+	stream,
+	task::SpawnExt,
+	FutureExt, SinkExt, StreamExt,
+};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result as RpcResult};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use log::warn;
+use sc_client_api::{AuxStore, BlockchainEvents, StorageProvider};
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
+use sc_service::TFullBackend;
 use sc_transaction_pool_api::TransactionPool;
+use serde::Serialize;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_runtime::{generic::BlockId, traits::Header as HeaderT};
 
-use manta_primitives::types::{AccountId, Balance, Block, Index as Nonce};
+use crate::manta_pay_index::{storage_key, SharedShardIndex};
+use manta_primitives::types::{AccountId, Balance, Block, Hash, Index as Nonce};
+use pallet_manta_pay::{
+	rpc::{Pull, PullApi},
+	runtime::MantaPayPullApi,
+	types::{EncryptedNote, MembershipPath, Utxo, VoidNumber},
+};
 
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
@@ -38,6 +59,10 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// MantaPay shard index, if enabled
+	pub manta_pay_index: Option<SharedShardIndex>,
+	/// Executor for subscription tasks
+	pub subscription_executor: SubscriptionTaskExecutor,
 }
 
 /// Instantiate all RPC extensions.
@@ -63,6 +88,7 @@ where
 		client,
 		pool,
 		deny_unsafe,
+		..
 	} = deps;
 
 	io.extend_with(SystemApi::to_delegate(FullSystem::new(
@@ -76,3 +102,367 @@ where
 
 	io
 }
+
+/// Instantiate all RPC extensions for Dolphin, which additionally serves the MantaPay APIs and
+/// the MantaPay ledger subscription.
+pub fn create_dolphin_full<C, P>(deps: FullDeps<C, P>) -> RpcExtension
+where
+	C: ProvideRuntimeApi<Block>
+		+ HeaderBackend<Block>
+		+ AuxStore
+		+ HeaderMetadata<Block, Error = BlockChainError>
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
+	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: BlockBuilder<Block>,
+	C: BlockchainEvents<Block> + StorageProvider<Block, TFullBackend<Block>>,
+	C::Api: MantaPayPullApi<Block>,
+	P: TransactionPool + Sync + Send + 'static,
+{
+	let client = deps.client.clone();
+	let manta_pay_index = deps.manta_pay_index.clone();
+	let ledger_subscription = LedgerSubscription::new(
+		client.clone(),
+		SubscriptionManager::new(Arc::new(deps.subscription_executor.clone())),
+		&deps.subscription_executor,
+	);
+	let mut io = create_full(deps);
+	io.extend_with(PullApi::to_delegate(Pull::new(client)));
+	io.extend_with(LedgerSubscriptionApi::to_delegate(ledger_subscription));
+	if let Some(index) = manta_pay_index {
+		io.extend_with(MembershipApi::to_delegate(Membership::new(index)));
+	}
+	io
+}
+
+/// MantaPay membership API.
+#[rpc]
+pub trait MembershipApi {
+	/// Returns the membership path of `utxo` in its shard tree at the latest block indexed by the
+	/// node, or `None` if `utxo` has not been indexed.
+	#[rpc(name = "mantaPay_getMembershipPath")]
+	fn get_membership_path(&self, utxo: Utxo) -> RpcResult<Option<MembershipPath>>;
+}
+
+/// MantaPay membership API implementation.
+pub struct Membership {
+	/// Shard index
+	index: SharedShardIndex,
+}
+
+impl Membership {
+	/// Builds a new [`Membership`] API implementation serving paths from `index`.
+	pub fn new(index: SharedShardIndex) -> Self {
+		Self { index }
+	}
+}
+
+impl MembershipApi for Membership {
+	fn get_membership_path(&self, utxo: Utxo) -> RpcResult<Option<MembershipPath>> {
+		let index = self.index.read().map_err(|_| RpcError {
+			code: ErrorCode::ServerError(2),
+			message: "The shard index is unavailable.".into(),
+			data: None,
+		})?;
+		match &*index {
+			Some(index) => Ok(index.membership_path(&utxo)),
+			_ => Err(RpcError {
+				code: ErrorCode::ServerError(3),
+				message: "The shard index is still loading.".into(),
+				data: None,
+			}),
+		}
+	}
+}
+
+/// MantaPay ledger additions of a single block.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerUpdate {
+	/// Block hash
+	pub block: Hash,
+	/// Whether the block has been finalized, along with its ancestors
+	pub finalized: bool,
+	/// Whether the block has been retracted from the best chain by a reorg, in which case its
+	/// additions have been undone
+	pub retracted: bool,
+	/// `(shard, index, utxo, note)` entries added to `Shards`
+	pub receivers: Vec<(u8, u64, Utxo, EncryptedNote)>,
+	/// Void numbers added to `VoidNumberSetInsertionOrder`, in insertion order
+	pub void_numbers: Vec<VoidNumber>,
+}
+
+/// MantaPay ledger subscription API.
+#[rpc]
+pub trait LedgerSubscriptionApi {
+	/// RPC metadata
+	type Metadata;
+
+	/// Subscribes to the MantaPay ledger additions of every new best block and newly finalized
+	/// block.
+	///
+	/// When a reorg changes the best chain, the blocks which left it are pushed again with the
+	/// `retracted` flag set, newest first, before the blocks of the new best chain. Blocks whose
+	/// additions cannot be read are reported with an error notification.
+	#[pubsub(
+		subscription = "mantaPay_ledger",
+		subscribe,
+		name = "mantaPay_subscribeLedger"
+	)]
+	fn subscribe_ledger(&self, metadata: Self::Metadata, subscriber: Subscriber<LedgerUpdate>);
+
+	/// Cancels a ledger subscription.
+	#[pubsub(
+		subscription = "mantaPay_ledger",
+		unsubscribe,
+		name = "mantaPay_unsubscribeLedger"
+	)]
+	fn unsubscribe_ledger(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
+}
+
+/// Senders of the active ledger subscriptions.
+type LedgerSenders = Arc<Mutex<Vec<UnboundedSender<Result<LedgerUpdate, RpcError>>>>>;
+
+/// MantaPay ledger subscription API implementation.
+pub struct LedgerSubscription {
+	/// Senders of the active subscriptions
+	senders: LedgerSenders,
+	/// Subscription manager
+	subscriptions: SubscriptionManager,
+}
+
+impl LedgerSubscription {
+	/// Builds a new [`LedgerSubscription`], spawning the task on `executor` which computes the
+	/// ledger update of every block once and sends it to all subscribers.
+	pub fn new<C>(
+		client: Arc<C>,
+		subscriptions: SubscriptionManager,
+		executor: &SubscriptionTaskExecutor,
+	) -> Self
+	where
+		C: BlockchainEvents<Block>
+			+ HeaderBackend<Block>
+			+ StorageProvider<Block, TFullBackend<Block>>
+			+ Send
+			+ Sync
+			+ 'static,
+	{
+		let senders = LedgerSenders::default();
+		if let Err(err) = executor.spawn(send_ledger_updates(client, senders.clone())) {
+			warn!(
+				"Unable to spawn the MantaPay ledger subscription task: {:?}",
+				err
+			);
+		}
+		Self {
+			senders,
+			subscriptions,
+		}
+	}
+}
+
+/// Reads the MantaPay storage item `name` at `key` in the state of `block`.
+fn read_storage<C, T>(client: &C, block: Hash, name: &[u8], key: &[u8]) -> Result<Option<T>, String>
+where
+	C: StorageProvider<Block, TFullBackend<Block>>,
+	T: Decode,
+{
+	client
+		.storage(&BlockId::Hash(block), &storage_key(name, key))
+		.map_err(|err| err.to_string())?
+		.map(|data| T::decode(&mut data.0.as_slice()))
+		.transpose()
+		.map_err(|err| err.to_string())
+}
+
+/// Returns the MantaPay ledger additions of the block with the given `hash`.
+fn ledger_update<C>(
+	client: &C,
+	hash: Hash,
+	finalized: bool,
+	retracted: bool,
+) -> Result<LedgerUpdate, String>
+where
+	C: HeaderBackend<Block> + StorageProvider<Block, TFullBackend<Block>>,
+{
+	let parent = *client
+		.header(BlockId::Hash(hash))
+		.map_err(|err| err.to_string())?
+		.ok_or("Unknown block.")?
+		.parent_hash();
+	let mut receivers = Vec::new();
+	for shard in 0..=u8::MAX {
+		let start = read_storage::<_, u64>(client, parent, b"ShardUtilization", &[shard])?
+			.unwrap_or_default();
+		let end = read_storage::<_, u64>(client, hash, b"ShardUtilization", &[shard])?
+			.unwrap_or_default();
+		for index in start..end {
+			let (utxo, note) = read_storage::<_, (Utxo, EncryptedNote)>(
+				client,
+				hash,
+				b"Shards",
+				&(shard, index).encode(),
+			)?
+			.ok_or("Missing shard entry.")?;
+			receivers.push((shard, index, utxo, note));
+		}
+	}
+	let counter = b"CounterForVoidNumberSetInsertionOrder";
+	let start = read_storage::<_, u32>(client, parent, counter, &[])?.unwrap_or_default();
+	let end = read_storage::<_, u32>(client, hash, counter, &[])?.unwrap_or_default();
+	let mut void_numbers = Vec::new();
+	for index in start as u64..end as u64 {
+		void_numbers.push(
+			read_storage(
+				client,
+				hash,
+				b"VoidNumberSetInsertionOrder",
+				&index.encode(),
+			)?
+			.ok_or("Missing void number.")?,
+		);
+	}
+	Ok(LedgerUpdate {
+		block: hash,
+		finalized,
+		retracted,
+		receivers,
+		void_numbers,
+	})
+}
+
+/// Returns the blocks finalized by finalizing the block with the given `hash` after the block
+/// `last_finalized`, oldest first.
+fn newly_finalized<C>(client: &C, last_finalized: Hash, hash: Hash) -> Result<Vec<Hash>, String>
+where
+	C: HeaderBackend<Block>,
+{
+	let last_number = client
+		.number(last_finalized)
+		.map_err(|err| err.to_string())?
+		.ok_or("Unknown block.")?;
+	let mut blocks = Vec::new();
+	let mut current = hash;
+	loop {
+		let header = client
+			.header(BlockId::Hash(current))
+			.map_err(|err| err.to_string())?
+			.ok_or("Unknown block.")?;
+		if *header.number() <= last_number {
+			break;
+		}
+		blocks.push(current);
+		current = *header.parent_hash();
+	}
+	blocks.reverse();
+	Ok(blocks)
+}
+
+/// Returns the error notification sent when the ledger update of the block with the given
+/// `hash` cannot be computed.
+fn ledger_update_error(hash: Hash, err: String) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(4),
+		message: format!(
+			"Unable to compute the MantaPay ledger update of {:?}.",
+			hash
+		),
+		data: Some(err.into()),
+	}
+}
+
+/// Computes the ledger update of every new best block and newly finalized block once, and
+/// sends it to every subscriber in `senders`.
+async fn send_ledger_updates<C>(client: Arc<C>, senders: LedgerSenders)
+where
+	C: BlockchainEvents<Block> + HeaderBackend<Block> + StorageProvider<Block, TFullBackend<Block>>,
+{
+	let imports = client.import_notification_stream();
+	let finality = client.finality_notification_stream();
+	let mut last_finalized = client.info().finalized_hash;
+	let mut notifications = stream::select(imports.map(Either::Left), finality.map(Either::Right));
+	while let Some(notification) = notifications.next().await {
+		let blocks = match notification {
+			Either::Left(notification) if notification.is_new_best => {
+				let mut blocks = Vec::new();
+				if let Some(tree_route) = &notification.tree_route {
+					blocks.extend(
+						tree_route
+							.retracted()
+							.iter()
+							.map(|block| Ok((block.hash, false, true))),
+					);
+					blocks.extend(
+						tree_route
+							.enacted()
+							.iter()
+							.map(|block| Ok((block.hash, false, false))),
+					);
+				}
+				blocks.push(Ok((notification.hash, false, false)));
+				blocks
+			}
+			Either::Right(notification) => {
+				let blocks = match newly_finalized(&*client, last_finalized, notification.hash) {
+					Ok(hashes) => hashes
+						.into_iter()
+						.map(|hash| Ok((hash, true, false)))
+						.collect(),
+					Err(err) => vec![Err(ledger_update_error(notification.hash, err))],
+				};
+				last_finalized = notification.hash;
+				blocks
+			}
+			_ => continue,
+		};
+		{
+			let mut senders = senders.lock().expect("Ledger senders lock is poisoned.");
+			senders.retain(|sender| !sender.is_closed());
+			if senders.is_empty() {
+				continue;
+			}
+		}
+		for block in blocks {
+			let update = block.and_then(|(hash, finalized, retracted)| {
+				ledger_update(&*client, hash, finalized, retracted)
+					.map_err(|err| ledger_update_error(hash, err))
+			});
+			senders
+				.lock()
+				.expect("Ledger senders lock is poisoned.")
+				.retain(|sender| sender.unbounded_send(update.clone()).is_ok());
+		}
+	}
+}
+
+impl LedgerSubscriptionApi for LedgerSubscription {
+	type Metadata = sc_rpc::Metadata;
+
+	fn subscribe_ledger(&self, _: Self::Metadata, subscriber: Subscriber<LedgerUpdate>) {
+		let (sender, receiver) = mpsc::unbounded();
+		self.senders
+			.lock()
+			.expect("Ledger senders lock is poisoned.")
+			.push(sender);
+		self.subscriptions.add(subscriber, |sink| {
+			receiver
+				.map(Ok::<_, ()>)
+				.forward(
+					sink.sink_map_err(|err| {
+						warn!("Unable to send MantaPay ledger update: {:?}", err)
+					}),
+				)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_ledger(&self, _: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+}
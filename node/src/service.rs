@@ -225,7 +225,8 @@ async fn start_node_impl<RuntimeApi, Executor, RB, BIQ, BIC>(
 	parachain_config: Configuration,
 	polkadot_config: Configuration,
 	id: ParaId,
-	_rpc_ext_builder: RB,
+	manta_pay_index: bool,
+	rpc_ext_builder: RB,
 	build_import_queue: BIQ,
 	build_consensus: BIC,
 ) -> sc_service::error::Result<(
@@ -251,8 +252,14 @@ where
 	sc_client_api::StateBackendFor<TFullBackend<Block>, Block>: sp_api::StateBackend<BlakeTwo256>,
 	Executor: sc_executor::NativeExecutionDispatch + 'static,
 	RB: Fn(
-			Arc<TFullClient<Block, RuntimeApi, Executor>>,
-		) -> Result<jsonrpc_core::IoHandler<sc_rpc::Metadata>, sc_service::Error>
+			rpc::FullDeps<
+				TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<Executor>>,
+				sc_transaction_pool::FullPool<
+					Block,
+					TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<Executor>>,
+				>,
+			>,
+		) -> rpc::RpcExtension
 		+ Send
 		+ 'static,
 	BIQ: FnOnce(
@@ -303,6 +310,15 @@ where
 
 	let client = params.client.clone();
 	let backend = params.backend.clone();
+	let manta_pay_index = manta_pay_index.then(|| {
+		let index = crate::manta_pay_index::new_shared_index();
+		task_manager.spawn_handle().spawn_blocking(
+			"manta-pay-index",
+			None,
+			crate::manta_pay_index::run(client.clone(), index.clone()),
+		);
+		index
+	});
 	let block_announce_validator = BlockAnnounceValidator::new(relay_chain_interface.clone(), id);
 
 	let force_authoring = parachain_config.force_authoring;
@@ -327,14 +343,16 @@ where
 		let client = client.clone();
 		let transaction_pool = transaction_pool.clone();
 
-		Box::new(move |deny_unsafe, _| {
+		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = rpc::FullDeps {
 				client: client.clone(),
 				pool: transaction_pool.clone(),
 				deny_unsafe,
+				manta_pay_index: manta_pay_index.clone(),
+				subscription_executor,
 			};
 
-			Ok(rpc::create_full(deps))
+			Ok(rpc_ext_builder(deps))
 		})
 	};
 
@@ -605,10 +623,18 @@ where
 }
 
 /// Start a calamari/manta parachain node.
-pub async fn start_parachain_node<RuntimeApi, Executor, AuraId: AppKey>(
+///
+/// The RPC extensions are built by `rpc_ext_builder` so that each runtime can expose the APIs it
+/// supports, see [`rpc::create_full`] and [`rpc::create_dolphin_full`].
+///
+/// If `manta_pay_index` is set, the MantaPay shard index service is started and handed to the
+/// RPC extensions, see [`crate::manta_pay_index`].
+pub async fn start_parachain_node<RuntimeApi, Executor, AuraId: AppKey, RB>(
 	parachain_config: Configuration,
 	polkadot_config: Configuration,
 	id: ParaId,
+	manta_pay_index: bool,
+	rpc_ext_builder: RB,
 ) -> sc_service::error::Result<(
 	TaskManager,
 	Arc<TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<Executor>>>,
@@ -634,12 +660,24 @@ where
 	Executor: sc_executor::NativeExecutionDispatch + 'static,
 	<<AuraId as AppKey>::Pair as Pair>::Signature:
 		TryFrom<Vec<u8>> + std::hash::Hash + sp_runtime::traits::Member + Codec,
+	RB: Fn(
+			rpc::FullDeps<
+				TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<Executor>>,
+				sc_transaction_pool::FullPool<
+					Block,
+					TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<Executor>>,
+				>,
+			>,
+		) -> rpc::RpcExtension
+		+ Send
+		+ 'static,
 {
 	start_node_impl::<RuntimeApi, Executor, _, _, _>(
 		parachain_config,
 		polkadot_config,
 		id,
-		|_| Ok(Default::default()),
+		manta_pay_index,
+		rpc_ext_builder,
 		parachain_build_import_queue::<_, _, AuraId>,
 		|client,
 		 prometheus_registry,
@@ -15,21 +15,38 @@
 // along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	benchmark::precomputed_coins::{
-		MINT, PRIVATE_TRANSFER, PRIVATE_TRANSFER_INPUT, RECLAIM, RECLAIM_INPUT,
+	types::{
+		decode, EncryptedNote, LedgerOperation, PrecomputedCoins, ShardReplayCursor,
+		ShieldingPolicy, TransferPostLayout, TransferPostV2, TransferShapeKind, UtxoMerkleTreePath,
+		UtxoRegistration, VersionedParameters, VersionedPrecomputedCoins, VersionedTransferPost,
+		VoidNumber,
 	},
-	Asset, Call, Config, Event, Pallet, TransferPost,
+	Asset, Call, Config, Event, Pallet, RemoteTransfer, TransferPost, Wrap,
 };
-use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
 use frame_system::RawOrigin;
+use manta_accounting::transfer::ReceiverLedger;
+use manta_crypto::merkle_tree::forest::Configuration as _;
+use manta_pay::config;
 use manta_primitives::{
 	assets::{AssetConfig, AssetRegistrar, FungibleLedger},
 	constants::DEFAULT_ASSET_ED,
 	types::{AssetId, Balance},
 };
-use scale_codec::Decode;
 
-mod precomputed_coins;
+/// Precomputed Coins generated by `src/bin/precompute_coins.rs`
+const PRECOMPUTED_COINS: &[u8] = include_bytes!("precomputed_coins.scale");
+
+/// Number of UTXOs Registered by the `register_utxos` Benchmark
+const REGISTERED_UTXOS: u32 = 8;
+
+/// Decodes the [`PRECOMPUTED_COINS`].
+#[inline]
+pub fn precomputed_coins() -> PrecomputedCoins {
+	VersionedPrecomputedCoins::decode_latest(PRECOMPUTED_COINS)
+		.expect("Unable to decode the precomputed coins.")
+}
 
 /// Asserts that the last event that has occured is the same as `event`.
 #[inline]
@@ -42,6 +59,60 @@ where
 	assert_eq!(events[events.len() - 1].event, event.into().into());
 }
 
+/// Returns the void numbers spent and the UTXOs registered by `post`, which must already have been
+/// posted to the ledger.
+#[inline]
+pub fn ledger_updates<T>(post: &TransferPost) -> (Vec<VoidNumber>, Vec<UtxoRegistration>)
+where
+	T: Config,
+{
+	let void_numbers = post
+		.sender_posts
+		.iter()
+		.map(|sender_post| sender_post.void_number)
+		.collect();
+	let mut registrations = crate::pallet::Shards::<T>::iter()
+		.filter(|(_, _, (utxo, _))| {
+			post.receiver_posts
+				.iter()
+				.any(|receiver_post| receiver_post.utxo == *utxo)
+		})
+		.map(|(shard_index, leaf_index, (utxo, note))| UtxoRegistration {
+			utxo,
+			note,
+			shard_index,
+			leaf_index,
+		})
+		.collect::<Vec<_>>();
+	registrations.sort_by_key(|registration| (registration.shard_index, registration.leaf_index));
+	(void_numbers, registrations)
+}
+
+/// Returns `count` UTXOs which fall into exactly `shards` distinct shards.
+#[inline]
+pub fn sample_utxos(count: u32, shards: u32) -> Vec<config::Utxo> {
+	let mut utxos = Vec::new();
+	let mut shard_indices = BTreeSet::new();
+	let mut index = 0u64;
+	while utxos.len() < count as usize {
+		let mut bytes = [0; 32];
+		bytes[..8].copy_from_slice(&index.to_le_bytes());
+		index += 1;
+		let utxo = decode::<config::Utxo, 32>(bytes).expect("Small integers are valid UTXOs.");
+		let shard_index = config::MerkleTreeConfiguration::tree_index(&utxo);
+		let fits = if shard_indices.contains(&shard_index) {
+			utxos.len() - shard_indices.len() < (count - shards) as usize
+		} else {
+			shard_indices.len() < shards as usize
+		};
+		if fits {
+			shard_indices.insert(shard_index);
+			utxos.push(utxo);
+		}
+	}
+	utxos
+}
+
 /// Init assets for manta-pay
 #[inline]
 pub fn init_asset<T>(owner: &T::AccountId, id: AssetId, value: Balance)
@@ -62,6 +133,7 @@ where
 		.expect("Unable to mint asset to its new owner.");
 	<T::AssetConfig as AssetConfig<T>>::FungibleLedger::mint(id, &pallet_account, DEFAULT_ASSET_ED)
 		.expect("Unable to mint existential deposit to pallet account.");
+	crate::pallet::ShieldingPolicies::<T>::insert(id, ShieldingPolicy::unrestricted());
 }
 
 benchmarks! {
@@ -69,51 +141,189 @@ benchmarks! {
 		let caller: T::AccountId = whitelisted_caller();
 		let origin = T::Origin::from(RawOrigin::Signed(caller.clone()));
 		init_asset::<T>(&caller, 8u32, 1_000_000u128);
-		let mint_post = TransferPost::decode(&mut &*MINT).unwrap();
+		let mint_post = precomputed_coins().mints[0].clone();
 		let asset = Asset::new(mint_post.asset_id.unwrap(), mint_post.sources[0]);
 	}: to_private (
 		RawOrigin::Signed(caller.clone()),
-		mint_post
+		mint_post.clone()
 	) verify {
 		// FIXME: add balance checking
-		assert_last_event::<T, _>(Event::ToPrivate { asset, source: caller });
+		let (_, registrations) = ledger_updates::<T>(&mint_post);
+		assert_last_event::<T, _>(Event::ToPrivate { asset, source: caller, registrations });
+	}
+
+	to_private_from {
+		let caller: T::AccountId = whitelisted_caller();
+		let source: T::AccountId = account("source", 0, 0);
+		init_asset::<T>(&source, 8u32, 1_000_000u128);
+		let mint_post = precomputed_coins().mints[0].clone();
+		let asset = Asset::new(mint_post.asset_id.unwrap(), mint_post.sources[0]);
+		Pallet::<T>::approve_to_private(
+			T::Origin::from(RawOrigin::Signed(source.clone())),
+			caller.clone(),
+			asset.id,
+			asset.value,
+		).unwrap();
+	}: to_private_from (
+		RawOrigin::Signed(caller.clone()),
+		source.clone(),
+		mint_post.clone()
+	) verify {
+		let (_, registrations) = ledger_updates::<T>(&mint_post);
+		assert_last_event::<T, _>(Event::ToPrivate { asset, source, registrations });
 	}
 
 	to_public {
 		let caller: T::AccountId = whitelisted_caller();
 		let origin = T::Origin::from(RawOrigin::Signed(caller.clone()));
 		init_asset::<T>(&caller, 8u32, 1_000_000u128);
-		for coin in RECLAIM_INPUT {
-			Pallet::<T>::to_private(
-				origin.clone(),
-				TransferPost::decode(&mut &**coin).unwrap()
-			).unwrap();
+		let (reclaim_input, reclaim_post) = precomputed_coins().reclaims[0].clone();
+		for coin in reclaim_input {
+			Pallet::<T>::to_private(origin.clone(), coin).unwrap();
 		}
-		let reclaim_post = TransferPost::decode(&mut &*RECLAIM).unwrap();
 	}: to_public (
 		RawOrigin::Signed(caller.clone()),
-		reclaim_post
+		reclaim_post.clone()
 	) verify {
 		// FIXME: add balance checking
-		assert_last_event::<T, _>(Event::ToPublic { asset: Asset::new(8, 10_000), sink: caller });
+		let (void_numbers, registrations) = ledger_updates::<T>(&reclaim_post);
+		assert_last_event::<T, _>(Event::ToPublic {
+			asset: Asset::new(8, 10_000),
+			sink: caller,
+			void_numbers,
+			registrations,
+		});
+	}
+
+	to_public_for {
+		let caller: T::AccountId = whitelisted_caller();
+		let origin = T::Origin::from(RawOrigin::Signed(caller.clone()));
+		let sink: T::AccountId = account("sink", 0, 0);
+		init_asset::<T>(&caller, 8u32, 1_000_000u128);
+		let (reclaim_input, reclaim_post) = precomputed_coins().reclaims[0].clone();
+		for coin in reclaim_input {
+			Pallet::<T>::to_private(origin.clone(), coin).unwrap();
+		}
+	}: to_public_for (
+		RawOrigin::Signed(caller.clone()),
+		reclaim_post.clone(),
+		sink.clone()
+	) verify {
+		let (void_numbers, registrations) = ledger_updates::<T>(&reclaim_post);
+		assert_last_event::<T, _>(Event::ToPublic {
+			asset: Asset::new(8, 10_000),
+			sink,
+			void_numbers,
+			registrations,
+		});
+	}
+
+	to_public_with_fee {
+		let caller: T::AccountId = whitelisted_caller();
+		let origin = T::Origin::from(RawOrigin::Signed(caller.clone()));
+		init_asset::<T>(&caller, 8u32, 1_000_000u128);
+		let (reclaim_input, reclaim_post) = precomputed_coins().reclaims[0].clone();
+		for coin in reclaim_input {
+			Pallet::<T>::to_private(origin.clone(), coin).unwrap();
+		}
+	}: to_public_with_fee (
+		RawOrigin::Signed(caller.clone()),
+		reclaim_post,
+		1_000
+	) verify {
+		assert_last_event::<T, _>(Event::ToPublicWithFee {
+			asset: Asset::new(8, 10_000),
+			relayer: caller,
+			relayer_fee: 1_000,
+		});
 	}
 
 	private_transfer {
 		let caller: T::AccountId = whitelisted_caller();
 		let origin = T::Origin::from(RawOrigin::Signed(caller.clone()));
 		init_asset::<T>(&caller, 8u32, 1_000_000u128);
-		for coin in PRIVATE_TRANSFER_INPUT {
-			Pallet::<T>::to_private(
-				origin.clone(),
-				TransferPost::decode(&mut &**coin).unwrap(),
-			).unwrap();
+		let (private_transfer_input, private_transfer_post) =
+			precomputed_coins().private_transfers[0].clone();
+		for coin in private_transfer_input {
+			Pallet::<T>::to_private(origin.clone(), coin).unwrap();
 		}
-		let private_transfer_post = TransferPost::decode(&mut &*PRIVATE_TRANSFER).unwrap();
 	}: private_transfer (
 		RawOrigin::Signed(caller.clone()),
-		private_transfer_post
+		private_transfer_post.clone()
 	) verify {
-		assert_last_event::<T, _>(Event::PrivateTransfer { origin: caller });
+		let (void_numbers, registrations) = ledger_updates::<T>(&private_transfer_post);
+		assert_last_event::<T, _>(Event::PrivateTransfer {
+			origin: Some(caller),
+			void_numbers,
+			registrations,
+		});
+	}
+
+	to_public_remote {
+		let caller: T::AccountId = whitelisted_caller();
+		let origin = T::Origin::from(RawOrigin::Signed(caller.clone()));
+		init_asset::<T>(&caller, 8u32, 1_000_000u128);
+		let (reclaim_input, reclaim_post) = precomputed_coins().reclaims[0].clone();
+		for coin in reclaim_input {
+			Pallet::<T>::to_private(origin.clone(), coin).unwrap();
+		}
+		let destination =
+			<T::RemoteTransfer as RemoteTransfer<T::AccountId>>::benchmark_destination();
+	}: to_public_remote (
+		RawOrigin::Signed(caller.clone()),
+		reclaim_post,
+		destination.clone(),
+		1_000_000_000
+	) verify {
+		assert_last_event::<T, _>(Event::ToPublicRemote {
+			asset: Asset::new(8, 10_000),
+			destination,
+		});
+	}
+
+	private_transfer_unsigned {
+		let caller: T::AccountId = whitelisted_caller();
+		let origin = T::Origin::from(RawOrigin::Signed(caller.clone()));
+		init_asset::<T>(&caller, 8u32, 1_000_000u128);
+		let (private_transfer_input, private_transfer_post) =
+			precomputed_coins().private_transfers[0].clone();
+		for coin in private_transfer_input {
+			Pallet::<T>::to_private(origin.clone(), coin).unwrap();
+		}
+	}: private_transfer_unsigned (
+		RawOrigin::None,
+		private_transfer_post.clone()
+	) verify {
+		let (void_numbers, registrations) = ledger_updates::<T>(&private_transfer_post);
+		assert_last_event::<T, _>(Event::PrivateTransfer {
+			origin: None,
+			void_numbers,
+			registrations,
+		});
+	}
+
+	transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		let origin = T::Origin::from(RawOrigin::Signed(caller.clone()));
+		init_asset::<T>(&caller, 8u32, 1_000_000u128);
+		let (private_transfer_input, private_transfer_post) =
+			precomputed_coins().private_transfers[0].clone();
+		for coin in private_transfer_input {
+			Pallet::<T>::to_private(origin.clone(), coin).unwrap();
+		}
+		let post = VersionedTransferPost::from(TransferPostV2::from(
+			config::TransferPost::try_from(private_transfer_post.clone()).unwrap(),
+		));
+	}: transfer (
+		RawOrigin::Signed(caller.clone()),
+		post
+	) verify {
+		let (void_numbers, registrations) = ledger_updates::<T>(&private_transfer_post);
+		assert_last_event::<T, _>(Event::PrivateTransfer {
+			origin: Some(caller),
+			void_numbers,
+			registrations,
+		});
 	}
 
 	public_transfer {
@@ -130,6 +340,149 @@ benchmarks! {
 		// FIXME: add balance checking
 		assert_last_event::<T, _>(Event::Transfer { asset, source: caller.clone(), sink, });
 	}
+
+	set_verifying_context {
+		let shape = TransferShapeKind::PrivateTransfer;
+		let parameters = VersionedParameters::new(1, Pallet::<T>::verifying_context(shape).into_owned());
+	}: set_verifying_context (
+		RawOrigin::Root,
+		shape,
+		parameters,
+		Some(TransferPostLayout::V1)
+	) verify {
+		assert_last_event::<T, _>(Event::VerifyingContextUpdated { shape, version: 1 });
+	}
+
+	register_transfer_shape {
+		let shape = TransferShapeKind::Custom { sources: 0, senders: 4, receivers: 2, sinks: 0 };
+		let parameters = VersionedParameters::new(
+			1,
+			Pallet::<T>::verifying_context(TransferShapeKind::PrivateTransfer).into_owned(),
+		);
+		let weight = 1_000_000_000;
+	}: register_transfer_shape (
+		RawOrigin::Root,
+		shape,
+		parameters,
+		Some(TransferPostLayout::V1),
+		weight
+	) verify {
+		assert_last_event::<T, _>(Event::TransferShapeRegistered { shape, version: 1, weight });
+	}
+
+	deregister_transfer_shape {
+		let shape = TransferShapeKind::Custom { sources: 0, senders: 4, receivers: 2, sinks: 0 };
+		let parameters = VersionedParameters::new(
+			1,
+			Pallet::<T>::verifying_context(TransferShapeKind::PrivateTransfer).into_owned(),
+		);
+		Pallet::<T>::register_transfer_shape(
+			T::Origin::from(RawOrigin::Root),
+			shape,
+			parameters,
+			Some(TransferPostLayout::V1),
+			0,
+		).unwrap();
+	}: deregister_transfer_shape (
+		RawOrigin::Root,
+		shape
+	) verify {
+		assert_last_event::<T, _>(Event::TransferShapeDeregistered { shape });
+	}
+
+	set_utxo_accumulator_model {
+		let parameters = VersionedParameters::new(1, Pallet::<T>::utxo_accumulator_model().into_owned());
+	}: set_utxo_accumulator_model (
+		RawOrigin::Root,
+		parameters
+	) verify {
+		assert_last_event::<T, _>(Event::UtxoAccumulatorModelUpdated { version: 1 });
+	}
+
+	set_shielding_policy {
+		let policy = Some(ShieldingPolicy::unrestricted());
+	}: set_shielding_policy (
+		RawOrigin::Root,
+		8,
+		policy
+	) verify {
+		assert_last_event::<T, _>(Event::ShieldingPolicyUpdated { asset_id: 8, policy });
+	}
+
+	approve_to_private {
+		let caller: T::AccountId = whitelisted_caller();
+		let spender: T::AccountId = account("spender", 0, 0);
+	}: approve_to_private (
+		RawOrigin::Signed(caller.clone()),
+		spender.clone(),
+		8,
+		1_000
+	) verify {
+		assert_last_event::<T, _>(Event::ToPrivateApproved {
+			owner: caller,
+			spender,
+			asset: Asset::new(8, 1_000),
+		});
+	}
+
+	set_frozen {
+		let operation = LedgerOperation::Mint;
+	}: set_frozen (
+		RawOrigin::Root,
+		operation,
+		Some(8),
+		true
+	) verify {
+		assert_last_event::<T, _>(Event::OperationFrozen { operation, asset_id: Some(8) });
+	}
+
+	replay_shards {
+		let l in 1 .. Pallet::<T>::SHARD_REPLAY_STEP;
+		let parameters = Pallet::<T>::decoded_utxo_accumulator_model().unwrap();
+		let mut tree = UtxoMerkleTreePath::default();
+		for leaf_index in 0..l as u64 {
+			let mut utxo = [0; 32];
+			utxo[..8].copy_from_slice(&leaf_index.to_le_bytes());
+			crate::pallet::Shards::<T>::insert(0, leaf_index, (utxo, EncryptedNote::default()));
+			let root = Pallet::<T>::replay_leaf(&parameters, &mut tree, utxo).unwrap();
+			crate::pallet::UtxoAccumulatorOutputs::<T>::insert(
+				root,
+				frame_system::Pallet::<T>::block_number(),
+			);
+		}
+		crate::pallet::ShardReplay::<T>::put(ShardReplayCursor::default());
+	}: {
+		Pallet::<T>::replay_shards(l);
+	} verify {
+		assert_eq!(
+			crate::pallet::ShardReplay::<T>::get().map(|cursor| cursor.leaf_index),
+			Some(l as u64),
+		);
+	}
+
+	register_utxos {
+		let s in 1 .. REGISTERED_UTXOS;
+		let note = precomputed_coins().mints[0].receiver_posts[0].encrypted_note.clone();
+		let registrations = sample_utxos(REGISTERED_UTXOS, s)
+			.into_iter()
+			.map(|utxo| (Wrap(utxo), note.clone().try_into().unwrap()))
+			.collect::<Vec<(_, config::EncryptedNote)>>();
+		let mut ledger = Pallet::<T>::ledger();
+	}: {
+		ledger.register_all(registrations, &(Wrap(()), ()));
+	} verify {
+		assert!(ledger.error.is_none());
+		assert_eq!(ledger.registrations.len(), REGISTERED_UTXOS as usize);
+		assert_eq!(
+			ledger
+				.registrations
+				.iter()
+				.map(|registration| registration.shard_index)
+				.collect::<BTreeSet<_>>()
+				.len(),
+			s as usize,
+		);
+	}
 }
 
 impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -1,183 +0,0 @@
-// Copyright 2019-2022 Manta Network.
-// This file is part of pallet-manta-pay.
-//
-// pallet-manta-pay is free software: you can redistribute it and/or modify
-// it under the terms of the GNU General Public License as published by
-// the Free Software Foundation, either version 3 of the License, or
-// (at your option) any later version.
-//
-// pallet-manta-pay is distributed in the hope that it will be useful,
-// but WITHOUT ANY WARRANTY; without even the implied warranty of
-// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
-// GNU General Public License for more details.
-//
-// You should have received a copy of the GNU General Public License
-// along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
-
-//! Precomputed Coins
-//!
-//! THIS FILE IS AUTOMATICALLY GENERATED by `src/bin/precompute_coins.rs`. DO NOT EDIT.
-
-pub(crate) const MINT: &[u8] = &[
-	1, 8, 0, 0, 0, 4, 160, 134, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 185, 130, 170, 226,
-	74, 3, 155, 160, 10, 150, 100, 122, 158, 234, 185, 254, 254, 22, 148, 171, 108, 233, 38, 4,
-	117, 199, 26, 13, 29, 95, 16, 115, 99, 137, 11, 192, 60, 50, 107, 90, 4, 216, 26, 108, 148, 78,
-	74, 119, 51, 235, 0, 121, 30, 226, 237, 150, 188, 28, 40, 241, 6, 138, 206, 0, 168, 178, 43,
-	249, 209, 80, 199, 27, 16, 48, 93, 211, 121, 143, 211, 130, 134, 243, 241, 35, 93, 244, 129,
-	203, 47, 239, 17, 167, 25, 126, 228, 39, 150, 132, 241, 224, 120, 149, 172, 211, 104, 132, 134,
-	161, 105, 203, 47, 104, 166, 114, 145, 113, 154, 45, 189, 49, 244, 177, 157, 58, 14, 227, 8,
-	158, 75, 151, 10, 162, 0, 78, 14, 164, 138, 22, 55, 47, 29, 164, 51, 66, 235, 0, 104, 1, 129,
-	5, 166, 140, 253, 76, 210, 102, 48, 148, 250, 150, 187, 210, 19, 63, 211, 24, 209, 35, 84, 96,
-	222, 85, 21, 11, 211, 87, 118, 215, 226, 65, 17, 241, 237, 15, 255, 229, 67, 89, 247, 186, 219,
-	96, 65, 180, 192, 239, 239, 189, 128, 51, 12, 53, 67, 55, 53, 114, 145, 13, 92, 105, 240, 111,
-	48, 97, 7, 141, 216, 12, 107, 176, 119, 139, 158, 132, 240, 200, 100, 166, 8, 111, 37, 5, 237,
-	114, 142, 220, 136, 156, 16, 62, 89, 146, 225, 28, 187, 82, 209, 155, 123, 250, 216, 97, 226,
-	15, 252, 197, 166, 94, 178, 119, 210, 233, 102, 149, 59, 189, 96, 116, 64, 55, 163, 126, 230,
-	172, 115, 180, 145, 70, 66, 161, 177, 75, 224, 135, 8, 63, 192, 121, 117, 47, 123, 90, 38, 34,
-	141, 118, 204, 232, 174, 6, 76, 245, 246, 111, 190, 186, 134, 167, 143, 13, 23, 86, 119, 224,
-	82, 74, 107, 83, 45, 141, 232, 32, 248, 44, 22,
-];
-
-pub(crate) const PRIVATE_TRANSFER_INPUT: &[&[u8]] = &[
-	&[
-		1, 8, 0, 0, 0, 4, 16, 39, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 159, 183, 12, 98,
-		199, 240, 56, 6, 200, 34, 41, 208, 78, 149, 130, 227, 140, 123, 124, 122, 188, 250, 81, 65,
-		224, 129, 130, 168, 181, 177, 136, 109, 158, 177, 96, 191, 29, 242, 152, 67, 3, 223, 72,
-		144, 7, 164, 232, 217, 215, 221, 139, 19, 96, 181, 123, 76, 186, 51, 78, 160, 42, 15, 21,
-		136, 169, 76, 221, 100, 252, 37, 252, 227, 88, 193, 172, 43, 43, 178, 40, 215, 127, 224,
-		57, 131, 191, 215, 85, 128, 186, 225, 32, 48, 60, 68, 66, 8, 92, 224, 228, 39, 149, 70,
-		179, 100, 41, 142, 25, 69, 84, 239, 130, 248, 189, 146, 55, 193, 171, 243, 199, 90, 155,
-		245, 79, 252, 178, 55, 98, 141, 33, 96, 102, 124, 0, 246, 238, 181, 127, 117, 231, 138, 69,
-		72, 145, 216, 121, 112, 195, 34, 110, 162, 49, 175, 149, 161, 138, 117, 23, 44, 17, 149,
-		48, 236, 31, 74, 100, 24, 57, 66, 142, 174, 19, 227, 107, 115, 160, 205, 252, 188, 117,
-		176, 4, 187, 184, 55, 235, 204, 214, 161, 147, 178, 217, 127, 147, 160, 161, 199, 32, 49,
-		84, 164, 204, 23, 46, 15, 51, 107, 12, 142, 47, 85, 231, 61, 164, 171, 229, 8, 48, 52, 65,
-		82, 129, 55, 102, 159, 23, 112, 193, 197, 24, 137, 8, 21, 56, 157, 151, 145, 240, 159, 62,
-		179, 177, 160, 204, 245, 239, 13, 217, 249, 44, 20, 236, 85, 225, 122, 159, 177, 140, 84,
-		14, 85, 176, 76, 144, 114, 211, 7, 96, 243, 146, 19, 102, 85, 22, 0, 22, 104, 9, 190, 60,
-		210, 155, 247, 14, 6, 183, 61, 106, 59, 241, 33, 136, 232, 253, 135, 116, 246, 210, 171,
-		25, 89, 165, 252, 192, 24, 133, 133, 166, 22, 216, 44, 149, 170, 8, 152, 112, 194, 119,
-		197, 215, 168, 0, 214, 186, 195, 149,
-	],
-	&[
-		1, 8, 0, 0, 0, 4, 32, 78, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 228, 93, 36, 120,
-		190, 129, 158, 45, 131, 179, 188, 51, 129, 45, 172, 168, 73, 134, 167, 213, 65, 251, 45,
-		114, 107, 197, 210, 72, 184, 86, 8, 7, 155, 124, 155, 44, 6, 238, 213, 99, 70, 42, 72, 11,
-		244, 59, 220, 192, 125, 5, 73, 186, 69, 68, 103, 89, 81, 20, 116, 214, 212, 205, 173, 141,
-		202, 9, 125, 131, 47, 222, 172, 218, 56, 165, 226, 29, 233, 79, 222, 253, 243, 125, 162,
-		192, 27, 214, 9, 202, 164, 251, 223, 16, 57, 175, 180, 15, 11, 157, 66, 130, 186, 132, 142,
-		111, 149, 40, 82, 10, 19, 41, 247, 105, 4, 224, 85, 89, 175, 235, 13, 187, 62, 74, 245,
-		239, 136, 100, 132, 73, 224, 131, 93, 159, 0, 241, 22, 119, 69, 39, 101, 223, 1, 180, 214,
-		134, 3, 125, 82, 221, 14, 94, 86, 227, 145, 152, 253, 195, 89, 60, 30, 107, 156, 176, 201,
-		50, 136, 152, 84, 156, 188, 159, 233, 152, 95, 168, 7, 138, 46, 24, 229, 239, 140, 70, 92,
-		179, 0, 227, 41, 114, 42, 209, 176, 238, 30, 206, 214, 172, 30, 99, 65, 98, 170, 34, 168,
-		119, 204, 194, 127, 198, 133, 190, 237, 18, 124, 231, 172, 70, 63, 167, 189, 231, 90, 166,
-		228, 61, 160, 222, 160, 111, 8, 168, 237, 209, 230, 199, 216, 82, 21, 235, 205, 109, 29, 9,
-		45, 209, 158, 62, 208, 30, 46, 242, 151, 22, 155, 230, 18, 139, 145, 210, 41, 46, 209, 37,
-		0, 159, 77, 147, 4, 76, 58, 31, 74, 39, 175, 64, 140, 51, 140, 90, 97, 247, 67, 32, 115,
-		135, 5, 209, 33, 175, 207, 81, 25, 86, 133, 6, 125, 118, 206, 129, 244, 196, 244, 155, 38,
-		138, 222, 16, 201, 229, 120, 134, 66, 18, 104, 230, 21, 188, 144, 205, 140, 30, 127, 14,
-		202, 61, 3,
-	],
-];
-
-pub(crate) const PRIVATE_TRANSFER: &[u8] = &[
-	0, 0, 8, 149, 141, 24, 70, 56, 223, 63, 113, 225, 101, 247, 12, 53, 81, 2, 70, 98, 80, 224,
-	108, 149, 149, 192, 180, 26, 52, 0, 10, 55, 12, 1, 36, 94, 47, 214, 59, 108, 233, 220, 81, 254,
-	195, 216, 28, 72, 243, 66, 14, 140, 195, 139, 107, 119, 249, 218, 203, 158, 91, 2, 96, 92, 193,
-	195, 72, 9, 127, 98, 5, 100, 128, 65, 63, 29, 54, 2, 32, 102, 47, 67, 168, 14, 130, 164, 77,
-	36, 8, 119, 47, 224, 151, 89, 114, 19, 99, 90, 2, 225, 5, 75, 74, 168, 89, 181, 108, 217, 220,
-	97, 98, 241, 221, 57, 51, 101, 94, 137, 141, 106, 255, 224, 191, 63, 189, 144, 21, 55, 105, 79,
-	111, 8, 136, 46, 143, 144, 67, 219, 201, 243, 48, 94, 10, 180, 33, 50, 215, 146, 189, 68, 7,
-	211, 147, 22, 89, 22, 61, 45, 77, 188, 71, 127, 7, 2, 181, 243, 203, 34, 220, 73, 195, 125,
-	164, 123, 216, 200, 132, 74, 158, 47, 218, 127, 224, 255, 224, 170, 86, 2, 243, 138, 211, 23,
-	88, 236, 24, 8, 240, 193, 214, 10, 138, 88, 57, 21, 228, 25, 123, 169, 233, 4, 141, 161, 169,
-	47, 129, 136, 181, 67, 198, 246, 75, 232, 97, 14, 241, 109, 42, 166, 199, 188, 68, 86, 77, 30,
-	190, 150, 255, 191, 222, 231, 11, 227, 155, 125, 152, 50, 31, 189, 185, 161, 109, 190, 202,
-	157, 108, 173, 158, 13, 172, 234, 151, 208, 175, 28, 204, 188, 48, 62, 89, 191, 118, 60, 98,
-	55, 189, 111, 157, 229, 205, 107, 233, 158, 17, 135, 65, 250, 153, 85, 22, 163, 69, 115, 244,
-	213, 242, 77, 138, 19, 12, 150, 131, 252, 33, 116, 63, 178, 184, 34, 167, 189, 132, 24, 172,
-	67, 227, 185, 103, 136, 253, 234, 194, 48, 24, 126, 34, 14, 175, 177, 248, 245, 120, 245, 224,
-	233, 72, 36, 172, 106, 172, 53, 212, 224, 189, 172, 211, 14, 53, 251, 46, 186, 156, 246, 77,
-	131, 251, 107, 225, 133, 12, 195, 135, 82, 218, 84, 145, 44, 254, 77, 105, 52, 18, 147, 2, 194,
-	183, 74, 230, 225, 51, 159, 107, 218, 151, 118, 120, 128, 64, 234, 104, 238, 90, 201, 233, 247,
-	102, 72, 0, 140, 231, 52, 121, 137, 246, 181, 102, 228, 64, 132, 5, 238, 193, 152, 191, 37, 0,
-	25, 123, 138, 168, 74, 179, 110, 17, 241, 200, 143, 232, 187, 216, 176, 56, 11, 90, 113, 43,
-	231, 244, 97, 120, 32, 252, 26, 88, 35, 3, 108, 46, 3, 43, 96, 183, 50, 196, 134, 107, 152, 11,
-	103, 78, 217, 84, 236, 97, 144, 78, 91, 16, 54, 29, 136, 0, 120, 130, 45, 248, 169, 242, 32, 7,
-	230, 95, 179, 208, 176, 251, 190, 85, 206, 102, 107, 39, 239, 19, 170, 75, 169, 228, 93, 96,
-	203, 177, 72, 58, 82, 64, 14, 139, 197, 26, 186, 105, 239, 25, 214, 209, 28, 2, 61, 88, 184,
-	33, 34, 119, 100, 89, 164, 186, 105, 35, 24, 186, 184, 2, 182, 137, 13, 67, 59, 43, 43, 12,
-	244, 117, 205, 202, 180, 108, 162, 35, 111, 133, 138, 17, 106, 65, 105, 237, 167, 142, 79, 240,
-	137, 65, 210, 214, 12, 30, 30, 62, 255, 101, 189, 56, 147, 236, 21, 104, 123, 157, 27, 142, 40,
-	23, 35, 190, 27, 82, 196, 6,
-];
-
-pub(crate) const RECLAIM_INPUT: &[&[u8]] = &[
-	&[
-		1, 8, 0, 0, 0, 4, 16, 39, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 128, 176, 193,
-		38, 145, 229, 208, 226, 218, 128, 10, 50, 99, 41, 109, 253, 125, 116, 100, 244, 82, 95,
-		242, 87, 165, 210, 5, 65, 24, 184, 221, 112, 135, 41, 233, 98, 174, 50, 66, 158, 142, 182,
-		55, 179, 215, 39, 252, 65, 239, 247, 32, 118, 211, 65, 158, 172, 197, 230, 87, 105, 247,
-		147, 197, 72, 56, 101, 214, 52, 24, 91, 2, 181, 64, 207, 148, 68, 11, 83, 209, 195, 134,
-		120, 73, 97, 212, 78, 253, 131, 179, 11, 100, 34, 20, 139, 147, 118, 47, 141, 17, 253, 70,
-		161, 164, 175, 248, 223, 190, 227, 188, 54, 146, 38, 137, 252, 205, 236, 67, 179, 216, 117,
-		160, 74, 236, 110, 66, 181, 241, 34, 134, 31, 163, 235, 0, 199, 64, 216, 208, 244, 48, 143,
-		31, 215, 130, 218, 240, 219, 125, 114, 77, 110, 121, 206, 69, 185, 195, 133, 7, 25, 84,
-		231, 66, 128, 11, 96, 254, 159, 79, 13, 11, 96, 210, 147, 111, 221, 249, 127, 90, 176, 153,
-		19, 16, 178, 21, 54, 202, 69, 213, 243, 206, 123, 116, 43, 84, 253, 152, 100, 57, 43, 3,
-		161, 178, 151, 144, 164, 221, 119, 161, 193, 26, 21, 113, 145, 241, 159, 57, 23, 224, 190,
-		186, 192, 31, 60, 170, 11, 248, 79, 211, 24, 21, 236, 196, 99, 183, 179, 121, 140, 14, 76,
-		74, 3, 126, 189, 116, 19, 51, 255, 53, 99, 184, 140, 229, 160, 58, 108, 12, 182, 118, 63,
-		236, 182, 107, 204, 143, 160, 153, 198, 26, 240, 158, 137, 150, 243, 153, 222, 148, 104,
-		150, 136, 207, 155, 47, 66, 141, 195, 229, 239, 174, 79, 93, 88, 181, 19, 167, 97, 169, 39,
-		255, 29, 34, 98, 204, 52, 38, 77, 162, 130, 34, 74, 138, 80, 161, 8, 245, 74, 13, 193, 70,
-		121, 82, 132, 117, 227, 49, 87, 5,
-	],
-	&[
-		1, 8, 0, 0, 0, 4, 32, 78, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 228, 210, 85, 0,
-		68, 231, 28, 46, 214, 210, 81, 196, 230, 180, 70, 194, 45, 3, 133, 200, 141, 201, 198, 111,
-		89, 130, 144, 15, 103, 70, 115, 115, 191, 136, 103, 200, 240, 183, 116, 232, 156, 243, 148,
-		228, 93, 70, 253, 65, 131, 0, 189, 103, 198, 45, 218, 238, 247, 59, 170, 60, 146, 159, 199,
-		181, 3, 47, 178, 191, 251, 70, 141, 115, 235, 63, 121, 41, 28, 219, 154, 92, 242, 72, 27,
-		88, 15, 46, 244, 121, 90, 25, 96, 8, 243, 164, 125, 93, 165, 176, 83, 112, 149, 98, 27,
-		251, 58, 212, 98, 137, 69, 236, 177, 91, 223, 117, 57, 242, 205, 36, 36, 200, 146, 180, 34,
-		208, 123, 178, 17, 254, 188, 185, 237, 161, 0, 160, 10, 124, 37, 73, 65, 167, 250, 162, 69,
-		144, 116, 250, 190, 144, 9, 175, 235, 202, 31, 8, 209, 71, 245, 221, 84, 26, 78, 115, 187,
-		163, 246, 149, 86, 203, 133, 188, 220, 31, 177, 174, 162, 11, 177, 159, 186, 165, 151, 28,
-		22, 185, 14, 246, 198, 128, 226, 46, 97, 229, 117, 89, 122, 69, 111, 117, 121, 150, 144,
-		151, 190, 38, 182, 106, 252, 183, 10, 154, 158, 246, 17, 234, 73, 168, 10, 123, 87, 139,
-		152, 177, 161, 51, 68, 141, 218, 150, 21, 94, 247, 99, 173, 233, 95, 76, 15, 74, 46, 23,
-		23, 4, 228, 97, 213, 232, 79, 102, 9, 233, 4, 43, 113, 44, 197, 129, 139, 59, 169, 35, 93,
-		240, 93, 132, 225, 189, 5, 187, 144, 167, 54, 56, 7, 213, 7, 32, 12, 65, 10, 100, 12, 212,
-		174, 15, 144, 183, 77, 20, 182, 52, 138, 68, 6, 225, 37, 208, 109, 58, 137, 171, 137, 147,
-		44, 45, 92, 163, 41, 88, 40, 50, 228, 37, 176, 74, 161, 91, 88, 115, 198, 51, 188, 20, 83,
-		155, 148,
-	],
-];
-
-pub(crate) const RECLAIM: &[u8] = &[
-	1, 8, 0, 0, 0, 0, 8, 181, 218, 94, 91, 229, 12, 93, 102, 103, 83, 128, 233, 35, 47, 43, 157,
-	125, 73, 218, 195, 135, 59, 41, 236, 140, 102, 173, 161, 125, 251, 14, 2, 65, 169, 122, 135,
-	41, 122, 69, 34, 114, 174, 83, 250, 231, 40, 132, 115, 136, 70, 202, 131, 95, 118, 10, 116, 82,
-	46, 5, 203, 39, 9, 200, 98, 243, 129, 186, 1, 55, 53, 4, 200, 146, 79, 133, 119, 144, 50, 173,
-	247, 74, 154, 80, 177, 159, 19, 166, 132, 205, 153, 205, 237, 110, 117, 254, 54, 176, 50, 183,
-	217, 132, 167, 234, 73, 198, 87, 167, 131, 52, 49, 113, 84, 94, 207, 55, 136, 193, 5, 203, 139,
-	211, 84, 9, 111, 25, 49, 151, 24, 4, 21, 106, 71, 113, 109, 83, 114, 123, 250, 211, 76, 64, 10,
-	217, 184, 133, 24, 92, 47, 31, 239, 179, 71, 45, 138, 192, 134, 148, 230, 63, 74, 11, 53, 182,
-	73, 110, 35, 147, 150, 235, 178, 207, 149, 85, 111, 169, 67, 12, 65, 186, 104, 142, 197, 123,
-	147, 205, 234, 64, 137, 195, 147, 171, 49, 204, 30, 111, 40, 182, 151, 215, 220, 6, 200, 199,
-	253, 245, 158, 221, 77, 194, 164, 165, 215, 204, 179, 218, 24, 116, 197, 134, 57, 254, 174,
-	197, 81, 81, 180, 21, 11, 177, 199, 148, 221, 245, 169, 115, 98, 165, 11, 31, 157, 88, 59, 26,
-	252, 235, 45, 184, 124, 252, 240, 128, 198, 10, 228, 238, 131, 113, 150, 95, 246, 180, 4, 16,
-	39, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 219, 183, 141, 210, 65, 237, 205, 219, 148, 194,
-	99, 219, 92, 13, 143, 190, 214, 137, 94, 119, 131, 77, 187, 255, 106, 239, 240, 156, 182, 215,
-	89, 159, 91, 170, 24, 106, 103, 44, 194, 155, 81, 232, 143, 7, 111, 38, 243, 25, 215, 83, 45,
-	88, 170, 161, 136, 54, 184, 214, 74, 150, 44, 249, 16, 80, 21, 182, 12, 68, 20, 39, 85, 25, 59,
-	108, 17, 133, 84, 68, 245, 41, 244, 228, 98, 203, 39, 1, 161, 97, 127, 175, 16, 160, 98, 187,
-	191, 15, 126, 19, 69, 0, 201, 2, 88, 203, 193, 200, 146, 15, 95, 248, 177, 104, 252, 141, 59,
-	213, 61, 190, 112, 97, 186, 146, 162, 239, 199, 14, 0, 51, 46, 47, 0, 221, 168, 189, 254, 171,
-	19, 95, 124, 201, 201, 228, 63, 17, 162, 55, 101, 251, 71, 65, 119, 162, 61, 68, 240, 205, 41,
-	128, 117, 216, 117, 187, 6, 134, 78, 102, 58, 69, 175, 79, 222, 189, 1, 254, 63, 174, 89, 76,
-	159, 135, 123, 78, 48, 4, 219, 127, 62, 176, 102, 15, 167, 4,
-];
@@ -16,8 +16,7 @@
 
 //! Precomputed Transactions
 
-use anyhow::Result;
-use indoc::indoc;
+use anyhow::{anyhow, bail, Result};
 use manta_accounting::{
 	asset::{Asset, AssetId},
 	transfer::{self, SpendingKey},
@@ -35,21 +34,24 @@ use manta_pay::config::{
 	VoidNumberCommitmentScheme,
 };
 use manta_util::codec::{Decode, IoReader};
-use pallet_manta_pay::types::TransferPost;
-use rand::thread_rng;
+use pallet_manta_pay::types::{PrecomputedCoins, TransferPost, VersionedPrecomputedCoins};
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
 use scale_codec::Encode;
 use std::{
 	env,
 	fs::{self, File, OpenOptions},
 	io::Write,
 	path::{Path, PathBuf},
+	str::FromStr,
 };
 
 /// UTXO Accumulator for Building Circuits
 type UtxoAccumulator =
 	TreeArrayMerkleForest<MerkleTreeConfiguration, Full<MerkleTreeConfiguration>, 256>;
 
-/// Loads parameters from the SDK, using `directory` as a temporary directory to store files.
+/// Loads parameters from the SDK, reading the proving contexts from `directory`.
+///
+/// With the `download` feature, missing proving contexts are first downloaded into `directory`.
 #[inline]
 fn load_parameters(
 	directory: &Path,
@@ -60,17 +62,37 @@ fn load_parameters(
 	UtxoAccumulatorModel,
 )> {
 	let mint_path = directory.join("mint.dat");
-	manta_sdk::pay::testnet::proving::Mint::download(&mint_path)?;
 	let private_transfer_path = directory.join("private-transfer.dat");
-	manta_sdk::pay::testnet::proving::PrivateTransfer::download(&private_transfer_path)?;
 	let reclaim_path = directory.join("reclaim.dat");
-	manta_sdk::pay::testnet::proving::Reclaim::download(&reclaim_path)?;
+	#[cfg(feature = "download")]
+	{
+		fs::create_dir_all(directory)?;
+		if !mint_path.exists() {
+			manta_sdk::pay::testnet::proving::Mint::download(&mint_path)?;
+		}
+		if !private_transfer_path.exists() {
+			manta_sdk::pay::testnet::proving::PrivateTransfer::download(&private_transfer_path)?;
+		}
+		if !reclaim_path.exists() {
+			manta_sdk::pay::testnet::proving::Reclaim::download(&reclaim_path)?;
+		}
+	}
+	let open = |path: &Path| {
+		File::open(path).map_err(|err| {
+			anyhow!(
+				"Unable to open the proving context {:?}: {}. Copy it into the parameters \
+				 directory, or build with the `download` feature to download it.",
+				path,
+				err
+			)
+		})
+	};
 	let proving_context = MultiProvingContext {
-		mint: ProvingContext::decode(IoReader(File::open(mint_path)?))
+		mint: ProvingContext::decode(IoReader(open(&mint_path)?))
 			.expect("Unable to decode MINT proving context."),
-		private_transfer: ProvingContext::decode(IoReader(File::open(private_transfer_path)?))
+		private_transfer: ProvingContext::decode(IoReader(open(&private_transfer_path)?))
 			.expect("Unable to decode PRIVATE_TRANSFER proving context."),
-		reclaim: ProvingContext::decode(IoReader(File::open(reclaim_path)?))
+		reclaim: ProvingContext::decode(IoReader(open(&reclaim_path)?))
 			.expect("Unable to decode RECLAIM proving context."),
 	};
 	let verifying_context = MultiVerifyingContext {
@@ -270,44 +292,130 @@ where
 	([mint_0.into(), mint_1.into()], reclaim.into())
 }
 
-/// Writes a new `const` definition to `$writer`.
-macro_rules! write_const_array {
-	($writer:ident, $name:ident, $value:expr) => {
-		writeln!(
-			$writer,
-			"pub(crate) const {}: &[u8] = &{:?};\n",
-			stringify!($name),
-			$value.encode().as_slice()
-		)
-	};
+/// Command Line Options
+#[derive(Debug)]
+struct Options {
+	/// Target File
+	target_file: PathBuf,
+
+	/// Directory with the Proving Contexts
+	parameters_dir: PathBuf,
+
+	/// RNG Seed
+	seed: u64,
+
+	/// Number of Mints
+	mints: usize,
+
+	/// Number of Private Transfers
+	private_transfers: usize,
+
+	/// Number of Reclaims
+	reclaims: usize,
+
+	/// Asset Ids, used in turn by each transaction of each shape
+	asset_ids: Vec<u32>,
 }
 
-/// Writes a new `const` definition to `$writer`.
-macro_rules! write_const_nested_array {
-	($writer:ident, $name:ident, $value:expr) => {
-		writeln!(
-			$writer,
-			"pub(crate) const {}: &[&[u8]] = &[{}];\n",
-			stringify!($name),
-			$value
-				.iter()
-				.flat_map(|v| {
-					format!("&{:?},", v.encode().as_slice())
-						.chars()
-						.collect::<Vec<_>>()
-				})
-				.collect::<String>(),
-		)
-	};
+impl Options {
+	/// Usage String
+	const USAGE: &'static str = concat!(
+		"Usage: precompute_coins [OPTIONS] [TARGET_FILE]\n",
+		"\n",
+		"Options:\n",
+		"    --parameters-dir <DIR>         Read the proving contexts from DIR [default: parameters]\n",
+		"    --seed <SEED>                  Seed the RNG with SEED [default: 0]\n",
+		"    --mints <COUNT>                Number of mints [default: 1]\n",
+		"    --private-transfers <COUNT>    Number of private transfers [default: 1]\n",
+		"    --reclaims <COUNT>             Number of reclaims [default: 1]\n",
+		"    --asset-ids <IDS>              Comma-separated asset ids used in turn [default: 8]",
+	);
+
+	/// Parses the command line options from `args`.
+	#[inline]
+	fn parse<I>(args: I) -> Result<Self>
+	where
+		I: IntoIterator<Item = String>,
+	{
+		let mut target_file = None;
+		let mut parameters_dir = None;
+		let mut seed = 0;
+		let mut mints = 1;
+		let mut private_transfers = 1;
+		let mut reclaims = 1;
+		let mut asset_ids = vec![8];
+		let mut args = args.into_iter();
+		while let Some(arg) = args.next() {
+			let mut value = || {
+				args.next()
+					.ok_or_else(|| anyhow!("Missing value for {}.\n\n{}", arg, Self::USAGE))
+			};
+			match arg.as_str() {
+				"--parameters-dir" => parameters_dir = Some(PathBuf::from(value()?)),
+				"--seed" => seed = parse_value(&value()?)?,
+				"--mints" => mints = parse_value(&value()?)?,
+				"--private-transfers" => private_transfers = parse_value(&value()?)?,
+				"--reclaims" => reclaims = parse_value(&value()?)?,
+				"--asset-ids" => {
+					asset_ids = value()?
+						.split(',')
+						.map(parse_value)
+						.collect::<Result<_>>()?
+				}
+				"-h" | "--help" => {
+					println!("{}", Self::USAGE);
+					std::process::exit(0);
+				}
+				flag if flag.starts_with("--") => {
+					bail!("Unknown option {}.\n\n{}", flag, Self::USAGE)
+				}
+				_ if target_file.is_none() => target_file = Some(PathBuf::from(&arg)),
+				_ => bail!("Unexpected argument {}.\n\n{}", arg, Self::USAGE),
+			}
+		}
+		if asset_ids.is_empty() {
+			bail!("At least one asset id is required.");
+		}
+		Ok(Self {
+			target_file: match target_file {
+				Some(target_file) => target_file,
+				_ => env::current_dir()?.join("precomputed_coins.scale"),
+			},
+			parameters_dir: match parameters_dir {
+				Some(parameters_dir) => parameters_dir,
+				_ => Path::new(env!("CARGO_MANIFEST_DIR")).join("parameters"),
+			},
+			seed,
+			mints,
+			private_transfers,
+			reclaims,
+			asset_ids,
+		})
+	}
+
+	/// Returns the asset id to use for the `index`-th transaction of a shape.
+	#[inline]
+	fn asset_id(&self, index: usize) -> AssetId {
+		AssetId(self.asset_ids[index % self.asset_ids.len()])
+	}
+}
+
+/// Parses a command line option value.
+#[inline]
+fn parse_value<T>(value: &str) -> Result<T>
+where
+	T: FromStr,
+{
+	value
+		.parse()
+		.map_err(|_| anyhow!("Invalid option value {}.", value))
 }
 
 /// Builds sample transactions for testing.
 #[inline]
 fn main() -> Result<()> {
-	let target_file = env::args()
-		.nth(1)
-		.map(PathBuf::from)
-		.unwrap_or(env::current_dir()?.join("precomputed_coins.rs"));
+	let options = Options::parse(env::args().skip(1))?;
+	let target_file = &options.target_file;
 	assert!(
 		!target_file.exists(),
 		"Specify a file to place the generated files: {:?}.",
@@ -319,80 +427,51 @@ fn main() -> Result<()> {
 			.expect("This file should have a parent."),
 	)?;
 
-	let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
-	println!("[INFO] Temporary Directory: {:?}", directory);
+	println!("[INFO] RNG Seed: {}", options.seed);
+	let mut rng = ChaCha20Rng::seed_from_u64(options.seed);
 
-	let mut rng = thread_rng();
 	let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
-		load_parameters(directory.path())?;
-	let asset_id: u32 = 8;
+		load_parameters(&options.parameters_dir)?;
 
-	let mint = sample_mint(
-		&proving_context.mint,
-		&verifying_context.mint,
-		&parameters,
-		&utxo_accumulator_model,
-		AssetId(asset_id).value(100_000),
-		&mut rng,
-	);
-	let (private_transfer_input, private_transfer) = sample_private_transfer(
-		&proving_context,
-		&verifying_context,
-		&parameters,
-		&utxo_accumulator_model,
-		AssetId(asset_id).value(10_000),
-		AssetId(asset_id).value(20_000),
-		&mut rng,
-	);
-	let (reclaim_input, reclaim) = sample_reclaim(
-		&proving_context,
-		&verifying_context,
-		&parameters,
-		&utxo_accumulator_model,
-		AssetId(asset_id).value(10_000),
-		AssetId(asset_id).value(20_000),
-		&mut rng,
-	);
+	let mut coins = PrecomputedCoins::default();
+	for i in 0..options.mints {
+		coins.mints.push(sample_mint(
+			&proving_context.mint,
+			&verifying_context.mint,
+			&parameters,
+			&utxo_accumulator_model,
+			options.asset_id(i).value(100_000),
+			&mut rng,
+		));
+	}
+	for i in 0..options.private_transfers {
+		coins.private_transfers.push(sample_private_transfer(
+			&proving_context,
+			&verifying_context,
+			&parameters,
+			&utxo_accumulator_model,
+			options.asset_id(i).value(10_000),
+			options.asset_id(i).value(20_000),
+			&mut rng,
+		));
+	}
+	for i in 0..options.reclaims {
+		coins.reclaims.push(sample_reclaim(
+			&proving_context,
+			&verifying_context,
+			&parameters,
+			&utxo_accumulator_model,
+			options.asset_id(i).value(10_000),
+			options.asset_id(i).value(20_000),
+			&mut rng,
+		));
+	}
 
-	let mut target_file = OpenOptions::new()
+	OpenOptions::new()
 		.create_new(true)
 		.write(true)
-		.open(target_file)?;
-
-	writeln!(
-		target_file,
-		indoc! {r"
-		    // Copyright 2019-2022 Manta Network.
-		    // This file is part of pallet-manta-pay.
-		    //
-		    // pallet-manta-pay is free software: you can redistribute it and/or modify
-		    // it under the terms of the GNU General Public License as published by
-		    // the Free Software Foundation, either version 3 of the License, or
-		    // (at your option) any later version.
-		    //
-		    // pallet-manta-pay is distributed in the hope that it will be useful,
-		    // but WITHOUT ANY WARRANTY; without even the implied warranty of
-		    // MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
-		    // GNU General Public License for more details.
-		    //
-		    // You should have received a copy of the GNU General Public License
-		    // along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
-
-		    //! Precomputed Coins
-		    //!
-		    //! THIS FILE IS AUTOMATICALLY GENERATED by `src/bin/precompute_coins.rs`. DO NOT EDIT.
-	    "}
-	)?;
-
-	write_const_array!(target_file, MINT, mint)?;
-	write_const_nested_array!(target_file, PRIVATE_TRANSFER_INPUT, private_transfer_input)?;
-	write_const_array!(target_file, PRIVATE_TRANSFER, private_transfer)?;
-	write_const_nested_array!(target_file, RECLAIM_INPUT, reclaim_input)?;
-	write_const_array!(target_file, RECLAIM, reclaim)?;
-
-	directory
-		.close()
-		.expect("Unable to delete temporary test directory.");
+		.open(target_file)?
+		.write_all(&VersionedPrecomputedCoins::from(coins).encode())?;
 
 	Ok(())
 }
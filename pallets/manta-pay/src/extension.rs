@@ -0,0 +1,143 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of pallet-manta-pay.
+//
+// pallet-manta-pay is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// pallet-manta-pay is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction Extensions
+
+use super::*;
+use core::fmt;
+use frame_support::traits::IsSubType;
+use sp_runtime::{
+	traits::{DispatchInfoOf, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+
+/// Check Void Numbers Signed Extension
+///
+/// Provides a transaction tag for every void number spent by a signed MantaPay transfer, so the
+/// transaction pool only keeps one of several transactions spending the same asset, and rejects
+/// transfers spending void numbers which are already in the void number set.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckVoidNumbers<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T> CheckVoidNumbers<T>
+where
+	T: Config + Send + Sync,
+{
+	/// Builds a new [`CheckVoidNumbers`] signed extension.
+	#[inline]
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+
+	/// Returns the sender posts of `call`, which spend void numbers.
+	#[inline]
+	fn sender_posts(call: &<T as frame_system::Config>::Call) -> Vec<&SenderPost>
+	where
+		<T as frame_system::Config>::Call: IsSubType<Call<T>>,
+	{
+		match call.is_sub_type() {
+			Some(Call::private_transfer { post })
+			| Some(Call::to_public { post })
+			| Some(Call::to_public_for { post, .. })
+			| Some(Call::to_public_with_fee { post, .. })
+			| Some(Call::to_public_remote { post, .. }) => post.sender_posts.iter().collect(),
+			Some(Call::transfer { post }) => post.sender_posts().iter().collect(),
+			_ => Vec::new(),
+		}
+	}
+}
+
+impl<T> Default for CheckVoidNumbers<T>
+where
+	T: Config + Send + Sync,
+{
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> fmt::Debug for CheckVoidNumbers<T>
+where
+	T: Config + Send + Sync,
+{
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CheckVoidNumbers")
+	}
+}
+
+impl<T> SignedExtension for CheckVoidNumbers<T>
+where
+	T: Config + Send + Sync,
+	<T as frame_system::Config>::Call: IsSubType<Call<T>>,
+{
+	const IDENTIFIER: &'static str = "CheckVoidNumbers";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	#[inline]
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		Ok(())
+	}
+
+	#[inline]
+	fn validate(
+		&self,
+		_: &Self::AccountId,
+		call: &Self::Call,
+		_: &DispatchInfoOf<Self::Call>,
+		_: usize,
+	) -> TransactionValidity {
+		let sender_posts = Self::sender_posts(call);
+		if sender_posts.is_empty() {
+			return Ok(ValidTransaction::default());
+		}
+		let mut void_numbers = Vec::new();
+		for sender_post in sender_posts {
+			if VoidNumberSet::<T>::contains_key(sender_post.void_number) {
+				return InvalidTransaction::Stale.into();
+			}
+			if void_numbers.contains(&sender_post.void_number) {
+				return InvalidTransaction::Call.into();
+			}
+			void_numbers.push(sender_post.void_number);
+		}
+		void_numbers
+			.into_iter()
+			.fold(
+				ValidTransaction::with_tag_prefix(Pallet::<T>::VOID_NUMBER_TAG_PREFIX),
+				|builder, void_number| builder.and_provides(void_number),
+			)
+			.build()
+	}
+
+	#[inline]
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		self.validate(who, call, info, len).map(|_| ())
+	}
+}
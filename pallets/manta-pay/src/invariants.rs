@@ -0,0 +1,106 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of pallet-manta-pay.
+//
+// pallet-manta-pay is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// pallet-manta-pay is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ledger Invariants
+
+use super::*;
+use frame_support::dispatch::GetStorageVersion;
+
+impl<T> Pallet<T>
+where
+	T: Config,
+{
+	/// Checks that the ledger storage is self-consistent.
+	///
+	/// # Note
+	///
+	/// This iterates over the entire ledger, so it is only meant to be used off-chain, for example
+	/// in the `try-runtime` upgrade hooks.
+	pub fn check_ledger_invariants() -> Result<(), &'static str> {
+		Self::check_shard_invariants()?;
+		Self::check_void_number_invariants()?;
+		Self::check_deposit_invariants()
+	}
+
+	/// Checks that the [`UtxoSet`] matches the [`Shards`] and that the [`ShardTrees`] match the
+	/// length of their shards.
+	fn check_shard_invariants() -> Result<(), &'static str> {
+		let mut shard_lengths = BTreeMap::<u8, u64>::new();
+		for (shard_index, _, (utxo, _)) in Shards::<T>::iter() {
+			if !UtxoSet::<T>::contains_key(utxo) {
+				return Err("shard contains a UTXO which is not in the UTXO set");
+			}
+			*shard_lengths.entry(shard_index).or_default() += 1;
+		}
+		if UtxoSet::<T>::iter_keys().count() as u64 != shard_lengths.values().sum::<u64>() {
+			return Err("UTXO set size does not match the number of shard entries");
+		}
+		for (shard_index, tree) in ShardTrees::<T>::iter() {
+			let length = shard_lengths.remove(&shard_index).unwrap_or_default();
+			let expected_length = match tree.leaf_digest {
+				Some(_) => {
+					merkle_tree::CurrentPath::<config::MerkleTreeConfiguration>::from(
+						tree.current_path,
+					)
+					.leaf_index()
+					.0 as u64 + 1
+				}
+				_ => 0,
+			};
+			if length != expected_length {
+				return Err("shard tree leaf index does not match the shard length");
+			}
+			if Self::on_chain_storage_version() >= 2
+				&& ShardUtilization::<T>::get(shard_index) != length
+			{
+				return Err("shard utilization does not match the shard length");
+			}
+		}
+		if !shard_lengths.is_empty() {
+			return Err("shard has entries but no shard tree");
+		}
+		Ok(())
+	}
+
+	/// Checks that the [`VoidNumberSet`] matches the entries of the [`VoidNumberSetInsertionOrder`]
+	/// and, from storage version `3`, its counter.
+	fn check_void_number_invariants() -> Result<(), &'static str> {
+		let size = VoidNumberSetInsertionOrder::<T>::iter_keys().count() as u64;
+		if Self::on_chain_storage_version() >= 3
+			&& VoidNumberSetInsertionOrder::<T>::count() as u64 != size
+		{
+			return Err("void number set size does not match its insertion order");
+		}
+		if VoidNumberSet::<T>::iter_keys().count() as u64 != size {
+			return Err("void number set size does not match the void number set");
+		}
+		Ok(())
+	}
+
+	/// Checks that the balance of the pallet account covers the [`ShieldedDeposits`] of every
+	/// asset.
+	pub(crate) fn check_deposit_invariants() -> Result<(), &'static str> {
+		let account = Self::account_id();
+		for (asset_id, deposit) in ShieldedDeposits::<T>::iter() {
+			let balance = FungibleLedger::<T>::balance(asset_id, &account)
+				.map_err(|_| "shielded asset is not a valid asset")?;
+			if balance < deposit {
+				return Err("pallet account balance does not cover the shielded deposits");
+			}
+		}
+		Ok(())
+	}
+}
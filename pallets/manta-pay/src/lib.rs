@@ -38,8 +38,20 @@
 //!
 //! * [`to_public`]: Converts a public asset into a private one.
 //! * [`to_private`]: Converts a private asset back into a public one.
+//! * [`to_private_from`]: Converts a public asset of an approving account into a private one.
+//! * [`to_public_for`]: Converts a private asset back into a public one for a sink account.
+//! * [`to_public_with_fee`]: Pays fees out of a private asset, reimbursing a relayer.
+//! * [`to_public_remote`]: Converts a private asset back into a public one on another chain.
 //! * [`private_transfer`]: Transfers assets between two private accounts.
+//! * [`private_transfer_unsigned`]: Transfers assets between two private accounts without a
+//!   signing account.
+//! * [`transfer`]: Posts a transfer of any supported [`VersionedTransferPost`] version.
 //! * [`public_transfer`]: Transfers assets between two public accounts.
+//! * [`set_verifying_context`]: Registers a new verifying context for a transfer shape.
+//! * [`set_utxo_accumulator_model`]: Registers new UTXO accumulator model parameters.
+//! * [`set_shielding_policy`]: Sets the shielding policy of an asset.
+//! * [`approve_to_private`]: Approves another account to shield public assets on one's behalf.
+//! * [`set_frozen`]: Freezes mints, private transfers or reclaims in an emergency.
 //!
 //! Please refer to the [`Call`] enum and its associated variants for documentation on each
 //! function.
@@ -48,16 +60,32 @@
 //!
 //! [`to_private`]: Pallet::to_private
 //! [`to_public`]: Pallet::to_public
+//! [`to_private_from`]: Pallet::to_private_from
+//! [`to_public_for`]: Pallet::to_public_for
+//! [`to_public_with_fee`]: Pallet::to_public_with_fee
+//! [`to_public_remote`]: Pallet::to_public_remote
 //! [`private_transfer`]: Pallet::private_transfer
+//! [`private_transfer_unsigned`]: Pallet::private_transfer_unsigned
+//! [`transfer`]: Pallet::transfer
 //! [`public_transfer`]: Pallet::public_transfer
+//! [`set_verifying_context`]: Pallet::set_verifying_context
+//! [`set_utxo_accumulator_model`]: Pallet::set_utxo_accumulator_model
+//! [`set_shielding_policy`]: Pallet::set_shielding_policy
+//! [`approve_to_private`]: Pallet::approve_to_private
+//! [`set_frozen`]: Pallet::set_frozen
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
 
-use alloc::{vec, vec::Vec};
-use core::marker::PhantomData;
-use frame_support::{transactional, PalletId};
+use alloc::{
+	borrow::Cow,
+	collections::{BTreeMap, BTreeSet},
+	vec,
+	vec::Vec,
+};
+use core::{cell::Cell, fmt::Debug, marker::PhantomData};
+use frame_support::{dispatch::DispatchResult, transactional, weights::Weight, PalletId};
 use manta_accounting::{
 	asset,
 	transfer::{
@@ -79,6 +107,7 @@ use manta_primitives::{
 use manta_util::codec::Decode as _;
 use scale_codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
+use sp_runtime::DispatchError;
 use types::*;
 
 pub use pallet::*;
@@ -93,6 +122,16 @@ mod test;
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmark;
 
+pub mod extension;
+mod invariants;
+pub mod migrations;
+
+#[cfg(feature = "rpc")]
+pub mod rpc;
+
+#[cfg(feature = "runtime")]
+pub mod runtime;
+
 pub mod types;
 pub mod weights;
 
@@ -102,11 +141,19 @@ pub mod pallet {
 	use super::*;
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
-	use sp_runtime::traits::AccountIdConversion;
+	use sp_runtime::{
+		traits::AccountIdConversion,
+		transaction_validity::{TransactionLongevity, TransactionPriority},
+		Percent,
+	};
+
+	/// Current Storage Version
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
 
 	/// Pallet
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// The module configuration trait.
@@ -123,6 +170,83 @@ pub mod pallet {
 
 		/// Pallet ID
 		type PalletId: Get<PalletId>;
+
+		/// The origin which may update the verifying contexts and the UTXO accumulator model.
+		type ParametersOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin which may freeze and unfreeze ledger operations in an emergency.
+		type FreezeOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Number of UTXO accumulator outputs kept valid for each shard
+		///
+		/// Once a shard has produced this many newer outputs, the oldest one expires and senders
+		/// can no longer prove membership against it.
+		#[pallet::constant]
+		type UtxoAccumulatorOutputWindow: Get<u32>;
+
+		/// Transaction pool priority of unsigned private transfers
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Number of blocks an unsigned private transfer stays valid in the transaction pool
+		#[pallet::constant]
+		type UnsignedLongevity: Get<TransactionLongevity>;
+
+		/// Handler for fees paid out of shielded balances
+		type OnShieldedFee: OnShieldedFee<Self::AccountId>;
+
+		/// Fraction of a shard's capacity past which a warning event is emitted
+		#[pallet::constant]
+		type ShardUtilizationThreshold: Get<Percent>;
+
+		/// Handler for sending reclaimed assets to other chains
+		type RemoteTransfer: RemoteTransfer<Self::AccountId>;
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// Validates unsigned private transfers.
+		///
+		/// Since unsigned private transfers pay no fees, their proofs are verified before they enter
+		/// the transaction pool, so every accepted transaction spends real, unspent assets. Each
+		/// void number is provided as a tag so that conflicting spends are deduplicated, and the
+		/// configured priority and longevity bound their lifetime in the pool.
+		#[inline]
+		fn validate_unsigned(_: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if let Call::private_transfer_unsigned { post } = call {
+				let void_numbers = Self::check_unsigned_private_transfer(post)?;
+				config::TransferPost::try_from(post.clone())
+					.map_err(|_| InvalidTransaction::Call)?
+					.validate(vec![], vec![], &Self::ledger())
+					.map_err(|_| InvalidTransaction::BadProof)?;
+				void_numbers
+					.into_iter()
+					.fold(
+						ValidTransaction::with_tag_prefix(Self::VOID_NUMBER_TAG_PREFIX)
+							.priority(T::UnsignedPriority::get())
+							.longevity(T::UnsignedLongevity::get())
+							.propagate(true),
+						|builder, void_number| builder.and_provides(void_number),
+					)
+					.build()
+			} else {
+				InvalidTransaction::Call.into()
+			}
+		}
+
+		/// Re-runs the cheap checks of [`Self::validate_unsigned`] before dispatch, leaving proof
+		/// verification to the call itself.
+		#[inline]
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			if let Call::private_transfer_unsigned { post } = call {
+				Self::check_unsigned_private_transfer(post)?;
+				Ok(())
+			} else {
+				Err(InvalidTransaction::Call.into())
+			}
+		}
 	}
 
 	/// Fungible Ledger Implementation for [`Config`]
@@ -130,22 +254,79 @@ pub mod pallet {
 		<<T as Config>::AssetConfig as AssetConfig<T>>::FungibleLedger;
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		#[inline]
+		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			let mut count: Weight = 0;
+			for (output, ()) in ExpiredUtxoAccumulatorOutputs::<T>::drain() {
+				UtxoAccumulatorOutputs::<T>::remove(output);
+				count += 1;
+			}
+			T::DbWeight::get()
+				.reads_writes(count + 1, 2 * count)
+				.saturating_add(Self::replay_shards(Self::SHARD_REPLAY_STEP))
+		}
+
+		#[inline]
+		fn on_runtime_upgrade() -> Weight {
+			Self::resize_utxo_accumulator_output_rings()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::check_ledger_invariants()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::check_ledger_invariants()
+		}
+	}
 
 	/// Shards of the merkle tree of UTXOs
 	#[pallet::storage]
 	pub(super) type Shards<T: Config> =
 		StorageDoubleMap<_, Identity, u8, Identity, u64, ([u8; 32], EncryptedNote), ValueQuery>;
 
+	/// Number of UTXOs in each shard
+	#[pallet::storage]
+	pub(super) type ShardUtilization<T: Config> = StorageMap<_, Identity, u8, u64, ValueQuery>;
+
 	/// Shard merkle trees
 	#[pallet::storage]
 	pub(super) type ShardTrees<T: Config> =
 		StorageMap<_, Identity, u8, UtxoMerkleTreePath, ValueQuery>;
 
-	/// Outputs of Utxo accumulator
+	/// Outputs of Utxo accumulator, with the block number they were created at
 	#[pallet::storage]
 	pub(super) type UtxoAccumulatorOutputs<T: Config> =
-		StorageMap<_, Identity, [u8; 32], (), ValueQuery>;
+		StorageMap<_, Identity, [u8; 32], T::BlockNumber, OptionQuery>;
+
+	/// Ring buffer of the most recent Utxo accumulator outputs of each shard
+	#[pallet::storage]
+	pub(super) type UtxoAccumulatorOutputRing<T: Config> =
+		StorageDoubleMap<_, Identity, u8, Identity, u32, [u8; 32], OptionQuery>;
+
+	/// Next slot to be written in the Utxo accumulator output ring buffer of each shard
+	#[pallet::storage]
+	pub(super) type UtxoAccumulatorOutputRingHead<T: Config> =
+		StorageMap<_, Identity, u8, u32, ValueQuery>;
+
+	/// Window the Utxo accumulator output ring buffers were last sized for
+	///
+	/// The ring buffers are resized in `on_runtime_upgrade` whenever this differs from
+	/// [`Config::UtxoAccumulatorOutputWindow`].
+	#[pallet::storage]
+	pub(super) type UtxoAccumulatorOutputRingWindow<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+	/// Utxo accumulator outputs evicted from their ring buffer, pruned in `on_initialize`
+	#[pallet::storage]
+	pub(super) type ExpiredUtxoAccumulatorOutputs<T: Config> =
+		StorageMap<_, Identity, [u8; 32], (), OptionQuery>;
+
+	/// Progress of the shard replay of the V0->V1 migration, advanced in `on_initialize`
+	#[pallet::storage]
+	pub(super) type ShardReplay<T: Config> = StorageValue<_, ShardReplayCursor, OptionQuery>;
 
 	/// Utxo set of MantaPay protocol
 	#[pallet::storage]
@@ -157,49 +338,401 @@ pub mod pallet {
 
 	/// Void number set insertion order
 	/// Each element of the key is an `u64` insertion order number of void number.
+	///
+	/// The number of entries in this map is the size of the [`VoidNumberSet`].
 	#[pallet::storage]
 	pub(super) type VoidNumberSetInsertionOrder<T: Config> =
-		StorageMap<_, Identity, u64, [u8; 32], ValueQuery>;
+		CountedStorageMap<_, Identity, u64, [u8; 32], ValueQuery>;
+
+	/// Verifying contexts for each transfer shape
+	///
+	/// Shapes without a registered verifying context use the built-in testnet verifying context.
+	#[pallet::storage]
+	pub(super) type VerifyingContexts<T: Config> =
+		StorageMap<_, Twox64Concat, TransferShapeKind, VersionedParameters, OptionQuery>;
+
+	/// Weights of the registered custom transfer shapes
+	///
+	/// Posts with a [`TransferShapeKind::Custom`] shape are only accepted if their shape is in this
+	/// map, and they are charged at least the registered weight.
+	#[pallet::storage]
+	pub(super) type CustomTransferShapes<T: Config> =
+		StorageMap<_, Twox64Concat, TransferShapeKind, Weight, OptionQuery>;
 
-	/// The size of Void Number Set
-	/// FIXME: this should be removed.
+	/// Layouts of the [`TransferPostV2`] posts verified by the registered verifying contexts
+	///
+	/// Registered along with the [`VerifyingContexts`]. Shapes without a registered layout use
+	/// [`TransferPostLayout::V1`].
 	#[pallet::storage]
-	pub(super) type VoidNumberSetSize<T: Config> = StorageValue<_, u64, ValueQuery>;
+	pub(super) type TransferPostLayouts<T: Config> =
+		StorageMap<_, Twox64Concat, TransferShapeKind, TransferPostLayout, OptionQuery>;
+
+	/// UTXO accumulator model parameters
+	///
+	/// If no model has been registered, the built-in testnet model is used.
+	#[pallet::storage]
+	pub(super) type UtxoAccumulatorModelParameters<T: Config> =
+		StorageValue<_, VersionedParameters, OptionQuery>;
+
+	/// Shielding policies for each asset
+	///
+	/// Assets without a shielding policy can be converted into private assets without restriction.
+	#[pallet::storage]
+	pub(super) type ShieldingPolicies<T: Config> =
+		StorageMap<_, Twox64Concat, AssetId, ShieldingPolicy, OptionQuery>;
+
+	/// Frozen ledger operations
+	///
+	/// An operation frozen for the `None` asset is frozen for every asset.
+	#[pallet::storage]
+	pub(super) type FrozenOperations<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		LedgerOperation,
+		Twox64Concat,
+		Option<AssetId>,
+		(),
+		OptionQuery,
+	>;
+
+	/// Public assets deposited into the pallet account by posts, for each asset
+	///
+	/// This grows with the sources and shrinks with the sinks of every post. Deposits made before
+	/// storage version `4` are seeded by [`Pallet::migrate_v3_to_v4`].
+	#[pallet::storage]
+	pub(super) type ShieldedDeposits<T: Config> =
+		StorageMap<_, Twox64Concat, AssetId, Balance, ValueQuery>;
+
+	/// Approvals to convert public assets into private ones on behalf of their owner
+	///
+	/// Maps an owner and a `(spender, asset_id)` pair to the value that the spender can still
+	/// withdraw from the owner with [`Pallet::to_private_from`].
+	#[pallet::storage]
+	pub(super) type ToPrivateApprovals<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		(T::AccountId, AssetId),
+		Balance,
+		OptionQuery,
+	>;
+
+	/// Genesis Configuration
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		/// Verifying contexts to register at genesis
+		pub verifying_contexts: Vec<(TransferShapeKind, VersionedParameters)>,
+
+		/// UTXO accumulator model to register at genesis
+		pub utxo_accumulator_model: Option<VersionedParameters>,
+
+		/// Shielding policies to set at genesis
+		pub shielding_policies: Vec<(AssetId, ShieldingPolicy)>,
+
+		/// Shielded UTXOs to register at genesis, as `(shard_index, utxo, note)` in insertion
+		/// order
+		pub utxos: Vec<(u8, Utxo, EncryptedNote)>,
+
+		/// Spent void numbers to register at genesis, in insertion order
+		pub void_numbers: Vec<VoidNumber>,
+
+		/// Public assets backing the genesis UTXOs, which are minted into the pallet account and
+		/// counted as [`ShieldedDeposits`]
+		pub balances: Vec<(AssetId, Balance)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		#[inline]
+		fn default() -> Self {
+			Self {
+				verifying_contexts: Default::default(),
+				utxo_accumulator_model: None,
+				shielding_policies: Default::default(),
+				utxos: Default::default(),
+				void_numbers: Default::default(),
+				balances: Default::default(),
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		#[inline]
+		fn build(&self) {
+			for (shape, parameters) in &self.verifying_contexts {
+				assert!(
+					Pallet::<T>::decode_verifying_context(&parameters.bytes).is_some(),
+					"Unable to decode the genesis verifying context for {:?}.",
+					shape,
+				);
+				Pallet::<T>::insert_verifying_context(*shape, parameters, None);
+			}
+			if let Some(parameters) = &self.utxo_accumulator_model {
+				assert!(
+					Pallet::<T>::decode_utxo_accumulator_model(&parameters.bytes).is_some(),
+					"Unable to decode the genesis UTXO accumulator model.",
+				);
+				Pallet::<T>::put_utxo_accumulator_model(parameters);
+			}
+			for (asset_id, policy) in &self.shielding_policies {
+				ShieldingPolicies::<T>::insert(asset_id, policy);
+			}
+			let mut utxos = BTreeSet::new();
+			let mut insertions = BTreeMap::<u8, u64>::new();
+			let registrations = self
+				.utxos
+				.iter()
+				.map(|(shard_index, utxo, note)| {
+					assert!(utxos.insert(*utxo), "Duplicate genesis UTXO.");
+					*insertions.entry(*shard_index).or_default() += 1;
+					let utxo = decode::<config::Utxo, 32>(*utxo)
+						.expect("Unable to decode the genesis UTXO.");
+					assert_eq!(
+						config::MerkleTreeConfiguration::tree_index(&utxo),
+						*shard_index,
+						"Genesis UTXO registered in the wrong shard.",
+					);
+					let note = note
+						.clone()
+						.try_into()
+						.expect("Unable to decode the genesis encrypted note.");
+					(Wrap(utxo), note)
+				})
+				.collect::<Vec<_>>();
+			for (shard_index, count) in insertions {
+				assert!(
+					count <= Pallet::<T>::SHARD_CAPACITY,
+					"Genesis UTXOs exceed the capacity of shard {}.",
+					shard_index,
+				);
+			}
+			UtxoAccumulatorOutputRingWindow::<T>::put(T::UtxoAccumulatorOutputWindow::get().max(1));
+			let mut ledger = Pallet::<T>::ledger();
+			ledger.register_all(registrations, &(Wrap(()), ()));
+			assert!(
+				ledger.error.is_none(),
+				"Unable to register the genesis UTXOs.",
+			);
+			for void_number in &self.void_numbers {
+				assert!(
+					!VoidNumberSet::<T>::contains_key(void_number),
+					"Duplicate genesis void number.",
+				);
+				VoidNumberSet::<T>::insert(void_number, ());
+				VoidNumberSetInsertionOrder::<T>::insert(
+					VoidNumberSetInsertionOrder::<T>::count() as u64,
+					void_number,
+				);
+			}
+			for (asset_id, balance) in &self.balances {
+				assert!(
+					!ShieldedDeposits::<T>::contains_key(asset_id),
+					"Duplicate genesis balance of asset {}.",
+					asset_id,
+				);
+				assert!(
+					FungibleLedger::<T>::mint(*asset_id, &Pallet::<T>::account_id(), *balance)
+						.is_ok(),
+					"Unable to mint the genesis balance of asset {}.",
+					asset_id,
+				);
+				ShieldedDeposits::<T>::insert(asset_id, balance);
+			}
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Transforms some public assets into private ones using `post`, withdrawing the public
 		/// assets from the `origin` account.
-		#[pallet::weight(T::WeightInfo::to_private())]
+		#[pallet::weight(Pallet::<T>::transfer_weight(post, T::WeightInfo::to_private()))]
 		#[transactional]
 		pub fn to_private(origin: OriginFor<T>, post: TransferPost) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
-			let mut ledger = Self::ledger();
-			Self::deposit_event(
-				config::TransferPost::try_from(post)
-					.map_err(|_| Error::<T>::InvalidSerializedForm)?
-					.post(vec![origin], vec![], &(), &mut ledger)
-					.map_err(Error::<T>::from)?
-					.convert(None),
-			);
-			Ok(().into())
+			let (event, weight) = Self::post_transaction(
+				None,
+				vec![origin],
+				vec![],
+				post,
+				T::WeightInfo::to_private(),
+			)?;
+			Self::deposit_event(event);
+			Ok(Some(weight).into())
+		}
+
+		/// Transforms some public assets into private ones using `post`, withdrawing the public
+		/// assets from the `source` account.
+		///
+		/// # Note
+		///
+		/// The `source` account must have approved `origin` to withdraw at least the value of
+		/// `post` with [`approve_to_private`](Self::approve_to_private). The approval is decreased
+		/// by the value of `post`.
+		#[pallet::weight(Pallet::<T>::transfer_weight(post, T::WeightInfo::to_private_from()))]
+		#[transactional]
+		pub fn to_private_from(
+			origin: OriginFor<T>,
+			source: T::AccountId,
+			post: TransferPost,
+		) -> DispatchResultWithPostInfo {
+			let origin = ensure_signed(origin)?;
+			let asset = match (post.asset_id, post.sources.as_slice()) {
+				(Some(asset_id), [value]) => Asset::new(asset_id, *value),
+				_ => return Err(Error::<T>::InvalidShape.into()),
+			};
+			ToPrivateApprovals::<T>::try_mutate_exists(
+				&source,
+				(origin, asset.id),
+				|approval| -> Result<_, Error<T>> {
+					let remaining = approval
+						.unwrap_or_default()
+						.checked_sub(asset.value)
+						.ok_or(Error::<T>::InsufficientApproval)?;
+					*approval = (remaining > 0).then(|| remaining);
+					Ok(())
+				},
+			)?;
+			let (event, weight) = Self::post_transaction(
+				None,
+				vec![source],
+				vec![],
+				post,
+				T::WeightInfo::to_private_from(),
+			)?;
+			Self::deposit_event(event);
+			Ok(Some(weight).into())
 		}
 
 		/// Transforms some private assets into public ones using `post`, depositing the public
 		/// assets in the `origin` account.
-		#[pallet::weight(T::WeightInfo::to_public())]
+		#[pallet::weight(Pallet::<T>::transfer_weight(post, T::WeightInfo::to_public()))]
 		#[transactional]
 		pub fn to_public(origin: OriginFor<T>, post: TransferPost) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
-			let mut ledger = Self::ledger();
-			Self::deposit_event(
-				config::TransferPost::try_from(post)
-					.map_err(|_| Error::<T>::InvalidSerializedForm)?
-					.post(vec![], vec![origin], &(), &mut ledger)
-					.map_err(Error::<T>::from)?
-					.convert(None),
-			);
-			Ok(().into())
+			let (event, weight) = Self::post_transaction(
+				None,
+				vec![],
+				vec![origin],
+				post,
+				T::WeightInfo::to_public(),
+			)?;
+			Self::deposit_event(event);
+			Ok(Some(weight).into())
+		}
+
+		/// Transforms some private assets into public ones using `post`, depositing the public
+		/// assets in the `sink` account.
+		///
+		/// # Note
+		///
+		/// In this transaction, `origin` is just signing the `post` and is not necessarily related
+		/// to the `sink` account.
+		#[pallet::weight(Pallet::<T>::transfer_weight(post, T::WeightInfo::to_public_for()))]
+		#[transactional]
+		pub fn to_public_for(
+			origin: OriginFor<T>,
+			post: TransferPost,
+			sink: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let (event, weight) = Self::post_transaction(
+				None,
+				vec![],
+				vec![sink],
+				post,
+				T::WeightInfo::to_public_for(),
+			)?;
+			Self::deposit_event(event);
+			Ok(Some(weight).into())
+		}
+
+		/// Pays fees out of the private assets reclaimed by `post`.
+		///
+		/// The reclaimed asset is first deposited in the `origin` account, which relays the
+		/// transaction and keeps `relayer_fee` of it as a reimbursement for the public transaction
+		/// fee. The rest is handed over to [`Config::OnShieldedFee`], so the owner of the private
+		/// asset pays fees without revealing a public account.
+		///
+		/// Both an [`Event::ToPublic`] for the reclaim and an [`Event::ToPublicWithFee`] for the
+		/// fee payment are emitted.
+		#[pallet::weight(Pallet::<T>::transfer_weight(post, T::WeightInfo::to_public_with_fee()))]
+		#[transactional]
+		pub fn to_public_with_fee(
+			origin: OriginFor<T>,
+			post: TransferPost,
+			relayer_fee: Balance,
+		) -> DispatchResultWithPostInfo {
+			let origin = ensure_signed(origin)?;
+			let (asset_id, reclaimed) = match (post.asset_id, post.sinks.as_slice()) {
+				(Some(asset_id), [reclaimed]) => (asset_id, *reclaimed),
+				_ => return Err(Error::<T>::InvalidShape.into()),
+			};
+			let fee = reclaimed
+				.checked_sub(relayer_fee)
+				.ok_or(Error::<T>::InvalidRelayerFee)?;
+			let (event, weight) = Self::post_transaction(
+				None,
+				vec![],
+				vec![origin.clone()],
+				post,
+				T::WeightInfo::to_public_with_fee(),
+			)?;
+			Self::deposit_event(event);
+			if fee > 0 {
+				T::OnShieldedFee::on_shielded_fee(&origin, Asset::new(asset_id, fee))
+					.map_err(Error::<T>::from)?;
+			}
+			Self::deposit_event(Event::ToPublicWithFee {
+				asset: Asset::new(asset_id, reclaimed),
+				relayer: origin,
+				relayer_fee,
+			});
+			Ok(Some(weight).into())
+		}
+
+		/// Transforms some private assets into public ones using `post`, and sends the public
+		/// assets to `destination` on another chain with [`Config::RemoteTransfer`], buying
+		/// `destination_weight` of execution there.
+		///
+		/// # Note
+		///
+		/// The reclaimed assets are sent straight out of the pallet account, so they are never held
+		/// by a local public account. In this transaction, `origin` is just signing the `post` and
+		/// is not necessarily related to the owner of the private assets. If sending the assets
+		/// fails, the whole transaction is rolled back.
+		#[pallet::weight(
+			Pallet::<T>::transfer_weight(post, Pallet::<T>::to_public_remote_weight())
+		)]
+		#[transactional]
+		pub fn to_public_remote(
+			origin: OriginFor<T>,
+			post: TransferPost,
+			destination: RemoteDestination<T>,
+			destination_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let asset = match (post.asset_id, post.sinks.as_slice()) {
+				(Some(asset_id), [reclaimed]) => Asset::new(asset_id, *reclaimed),
+				_ => return Err(Error::<T>::InvalidShape.into()),
+			};
+			let (event, weight) = Self::post_transaction(
+				None,
+				vec![],
+				vec![Self::account_id()],
+				post,
+				Self::to_public_remote_weight(),
+			)?;
+			Self::deposit_event(event);
+			T::RemoteTransfer::transfer(
+				Self::account_id(),
+				asset,
+				destination.clone(),
+				destination_weight,
+			)?;
+			Self::deposit_event(Event::ToPublicRemote { asset, destination });
+			Ok(Some(weight).into())
 		}
 
 		/// Transfers private assets encoded in `post`.
@@ -208,22 +741,69 @@ pub mod pallet {
 		///
 		/// In this transaction, `origin` is just signing the `post` and is not necessarily related
 		/// to any of the participants in the transaction itself.
-		#[pallet::weight(T::WeightInfo::private_transfer())]
+		#[pallet::weight(Pallet::<T>::transfer_weight(post, T::WeightInfo::private_transfer()))]
 		#[transactional]
 		pub fn private_transfer(
 			origin: OriginFor<T>,
 			post: TransferPost,
 		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
-			let mut ledger = Self::ledger();
-			Self::deposit_event(
-				config::TransferPost::try_from(post)
-					.map_err(|_| Error::<T>::InvalidSerializedForm)?
-					.post(vec![], vec![], &(), &mut ledger)
-					.map_err(Error::<T>::from)?
-					.convert(Some(origin)),
-			);
-			Ok(().into())
+			let (event, weight) = Self::post_transaction(
+				Some(origin),
+				vec![],
+				vec![],
+				post,
+				T::WeightInfo::private_transfer(),
+			)?;
+			Self::deposit_event(event);
+			Ok(Some(weight).into())
+		}
+
+		/// Transfers private assets encoded in `post` without a signing account.
+		///
+		/// # Note
+		///
+		/// This transaction does not pay any fees, so it is only accepted into the transaction pool
+		/// once the shape, void numbers, accumulator outputs and proof of `post` have been checked.
+		/// See the [`ValidateUnsigned`] implementation of this pallet.
+		#[pallet::weight(
+			Pallet::<T>::transfer_weight(post, T::WeightInfo::private_transfer_unsigned())
+		)]
+		#[transactional]
+		pub fn private_transfer_unsigned(
+			origin: OriginFor<T>,
+			post: TransferPost,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			let (event, weight) = Self::post_transaction(
+				None,
+				vec![],
+				vec![],
+				post,
+				T::WeightInfo::private_transfer_unsigned(),
+			)?;
+			Self::deposit_event(event);
+			Ok(Some(weight).into())
+		}
+
+		/// Posts the versioned `post`, withdrawing its sources from and depositing its sinks in
+		/// the `origin` account.
+		///
+		/// # Note
+		///
+		/// The proof and ciphertexts of `post` must have the [`TransferPostLayout`] registered for
+		/// its shape. If `post` has no public participants, `origin` is just signing it like in
+		/// [`private_transfer`](Self::private_transfer).
+		#[pallet::weight(Pallet::<T>::versioned_transfer_weight(post))]
+		#[transactional]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			post: VersionedTransferPost,
+		) -> DispatchResultWithPostInfo {
+			let origin = ensure_signed(origin)?;
+			let (event, weight) = Self::post_versioned_transaction(origin, post)?;
+			Self::deposit_event(event);
+			Ok(Some(weight).into())
 		}
 
 		/// Transfers public `asset` from `origin` to the `sink` account.
@@ -244,6 +824,229 @@ pub mod pallet {
 			});
 			Ok(().into())
 		}
+
+		/// Registers the verifying context in `parameters` for transfers of the given `shape`,
+		/// along with the `layout` of the [`TransferPostV2`] posts it verifies, which defaults to
+		/// [`TransferPostLayout::V1`] and must be one of the [`TransferPostLayout::SUPPORTED`]
+		/// layouts.
+		///
+		/// The version of `parameters` must be strictly greater than the version of the verifying
+		/// context currently registered for `shape`.
+		#[pallet::weight(T::WeightInfo::set_verifying_context())]
+		#[transactional]
+		pub fn set_verifying_context(
+			origin: OriginFor<T>,
+			shape: TransferShapeKind,
+			parameters: VersionedParameters,
+			layout: Option<TransferPostLayout>,
+		) -> DispatchResultWithPostInfo {
+			T::ParametersOrigin::ensure_origin(origin)?;
+			ensure!(
+				Self::is_registered_shape(shape),
+				Error::<T>::UnknownTransferShape
+			);
+			if let Some(current) = VerifyingContexts::<T>::get(shape) {
+				ensure!(
+					parameters.version > current.version,
+					Error::<T>::StaleParametersVersion
+				);
+			}
+			ensure!(
+				Self::decode_verifying_context(&parameters.bytes).is_some(),
+				Error::<T>::InvalidParameters
+			);
+			ensure!(
+				layout.map_or(true, |layout| layout.is_supported()),
+				Error::<T>::UnsupportedTransferPostLayout
+			);
+			let version = parameters.version;
+			Self::insert_verifying_context(shape, &parameters, layout);
+			Self::deposit_event(Event::VerifyingContextUpdated { shape, version });
+			Ok(().into())
+		}
+
+		/// Registers the custom transfer `shape` with the verifying context in `parameters`,
+		/// charging at least `weight` for every post of that shape.
+		///
+		/// Like in [`set_verifying_context`](Self::set_verifying_context), `layout` is the layout
+		/// of the [`TransferPostV2`] posts of that shape, which defaults to
+		/// [`TransferPostLayout::V1`] and must be one of the [`TransferPostLayout::SUPPORTED`]
+		/// layouts.
+		///
+		/// If `shape` is already registered, its weight is replaced and the version of
+		/// `parameters` must be strictly greater than the version of its current verifying
+		/// context.
+		#[pallet::weight(T::WeightInfo::register_transfer_shape())]
+		#[transactional]
+		pub fn register_transfer_shape(
+			origin: OriginFor<T>,
+			shape: TransferShapeKind,
+			parameters: VersionedParameters,
+			layout: Option<TransferPostLayout>,
+			weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			T::ParametersOrigin::ensure_origin(origin)?;
+			ensure!(shape.is_valid_custom(), Error::<T>::InvalidShape);
+			if let Some(current) = VerifyingContexts::<T>::get(shape) {
+				ensure!(
+					parameters.version > current.version,
+					Error::<T>::StaleParametersVersion
+				);
+			}
+			ensure!(
+				Self::decode_verifying_context(&parameters.bytes).is_some(),
+				Error::<T>::InvalidParameters
+			);
+			ensure!(
+				layout.map_or(true, |layout| layout.is_supported()),
+				Error::<T>::UnsupportedTransferPostLayout
+			);
+			let version = parameters.version;
+			Self::insert_verifying_context(shape, &parameters, layout);
+			CustomTransferShapes::<T>::insert(shape, weight);
+			Self::deposit_event(Event::TransferShapeRegistered {
+				shape,
+				version,
+				weight,
+			});
+			Ok(().into())
+		}
+
+		/// Deregisters the custom transfer `shape` along with its verifying context, rejecting any
+		/// later post of that shape.
+		#[pallet::weight(T::WeightInfo::deregister_transfer_shape())]
+		#[transactional]
+		pub fn deregister_transfer_shape(
+			origin: OriginFor<T>,
+			shape: TransferShapeKind,
+		) -> DispatchResultWithPostInfo {
+			T::ParametersOrigin::ensure_origin(origin)?;
+			ensure!(
+				CustomTransferShapes::<T>::take(shape).is_some(),
+				Error::<T>::UnknownTransferShape
+			);
+			VerifyingContexts::<T>::remove(shape);
+			TransferPostLayouts::<T>::remove(shape);
+			Self::deposit_event(Event::TransferShapeDeregistered { shape });
+			Ok(().into())
+		}
+
+		/// Registers the UTXO accumulator model in `parameters`.
+		///
+		/// # Note
+		///
+		/// The shard trees are built with the accumulator model so it can only be replaced while
+		/// the ledger has no UTXOs.
+		#[pallet::weight(T::WeightInfo::set_utxo_accumulator_model())]
+		#[transactional]
+		pub fn set_utxo_accumulator_model(
+			origin: OriginFor<T>,
+			parameters: VersionedParameters,
+		) -> DispatchResultWithPostInfo {
+			T::ParametersOrigin::ensure_origin(origin)?;
+			if let Some(current) = UtxoAccumulatorModelParameters::<T>::get() {
+				ensure!(
+					parameters.version > current.version,
+					Error::<T>::StaleParametersVersion
+				);
+			}
+			ensure!(
+				ShardTrees::<T>::iter_keys().next().is_none(),
+				Error::<T>::LedgerNotEmpty
+			);
+			ensure!(
+				Self::decode_utxo_accumulator_model(&parameters.bytes).is_some(),
+				Error::<T>::InvalidParameters
+			);
+			let version = parameters.version;
+			Self::put_utxo_accumulator_model(&parameters);
+			Self::deposit_event(Event::UtxoAccumulatorModelUpdated { version });
+			Ok(().into())
+		}
+
+		/// Sets the shielding policy of the asset with the given `asset_id`, or removes it if
+		/// `policy` is `None`, which lifts every restriction on shielding the asset.
+		#[pallet::weight(T::WeightInfo::set_shielding_policy())]
+		#[transactional]
+		pub fn set_shielding_policy(
+			origin: OriginFor<T>,
+			asset_id: AssetId,
+			policy: Option<ShieldingPolicy>,
+		) -> DispatchResultWithPostInfo {
+			T::ParametersOrigin::ensure_origin(origin)?;
+			FungibleLedger::<T>::ensure_valid(asset_id).map_err(Error::<T>::from)?;
+			match policy {
+				Some(policy) => ShieldingPolicies::<T>::insert(asset_id, policy),
+				_ => ShieldingPolicies::<T>::remove(asset_id),
+			}
+			Self::deposit_event(Event::ShieldingPolicyUpdated { asset_id, policy });
+			Ok(().into())
+		}
+
+		/// Approves `spender` to convert up to `value` of the public assets with the given
+		/// `asset_id` held by `origin` into private ones with
+		/// [`to_private_from`](Self::to_private_from).
+		///
+		/// Any previous approval of `spender` for the same asset is replaced, and a `value` of zero
+		/// revokes it.
+		#[pallet::weight(T::WeightInfo::approve_to_private())]
+		#[transactional]
+		pub fn approve_to_private(
+			origin: OriginFor<T>,
+			spender: T::AccountId,
+			asset_id: AssetId,
+			value: Balance,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			FungibleLedger::<T>::ensure_valid(asset_id).map_err(Error::<T>::from)?;
+			if value > 0 {
+				ToPrivateApprovals::<T>::insert(&owner, (spender.clone(), asset_id), value);
+			} else {
+				ToPrivateApprovals::<T>::remove(&owner, (spender.clone(), asset_id));
+			}
+			Self::deposit_event(Event::ToPrivateApproved {
+				owner,
+				spender,
+				asset: Asset::new(asset_id, value),
+			});
+			Ok(().into())
+		}
+
+		/// Freezes `operation` for the asset with the given `asset_id`, or for every asset if
+		/// `asset_id` is `None`, or unfreezes it if `frozen` is `false`.
+		///
+		/// # Note
+		///
+		/// Freezing an operation for every asset does not clear the freezes of single assets, which
+		/// still apply once the operation is unfrozen for every asset.
+		#[pallet::weight(T::WeightInfo::set_frozen())]
+		#[transactional]
+		pub fn set_frozen(
+			origin: OriginFor<T>,
+			operation: LedgerOperation,
+			asset_id: Option<AssetId>,
+			frozen: bool,
+		) -> DispatchResultWithPostInfo {
+			T::FreezeOrigin::ensure_origin(origin)?;
+			ensure!(
+				operation != LedgerOperation::PrivateTransfer || asset_id.is_none(),
+				Error::<T>::InvalidFreeze
+			);
+			if frozen {
+				FrozenOperations::<T>::insert(operation, asset_id, ());
+				Self::deposit_event(Event::OperationFrozen {
+					operation,
+					asset_id,
+				});
+			} else {
+				FrozenOperations::<T>::remove(operation, asset_id);
+				Self::deposit_event(Event::OperationUnfrozen {
+					operation,
+					asset_id,
+				});
+			}
+			Ok(().into())
+		}
 	}
 
 	/// Event
@@ -269,12 +1072,23 @@ pub mod pallet {
 
 			/// Source Account
 			source: T::AccountId,
+
+			/// Registered UTXOs
+			registrations: Vec<UtxoRegistration>,
 		},
 
 		/// Private Transfer Event
 		PrivateTransfer {
 			/// Origin Account
-			origin: T::AccountId,
+			///
+			/// This is `None` for unsigned private transfers.
+			origin: Option<T::AccountId>,
+
+			/// Spent Void Numbers
+			void_numbers: Vec<VoidNumber>,
+
+			/// Registered UTXOs
+			registrations: Vec<UtxoRegistration>,
 		},
 
 		/// To Public Event
@@ -284,6 +1098,123 @@ pub mod pallet {
 
 			/// Sink Account
 			sink: T::AccountId,
+
+			/// Spent Void Numbers
+			void_numbers: Vec<VoidNumber>,
+
+			/// Registered UTXOs
+			registrations: Vec<UtxoRegistration>,
+		},
+
+		/// Fee-Sponsored To Public Event
+		ToPublicWithFee {
+			/// Asset Reclaimed to Pay Fees
+			asset: Asset,
+
+			/// Relayer Account
+			relayer: T::AccountId,
+
+			/// Amount Kept by the Relayer
+			relayer_fee: Balance,
+		},
+
+		/// To Public Remote Event
+		ToPublicRemote {
+			/// Asset Sent
+			asset: Asset,
+
+			/// Destination on the Remote Chain
+			destination: RemoteDestination<T>,
+		},
+
+		/// Shard Utilization Threshold Reached Event
+		ShardUtilizationThresholdReached {
+			/// Shard Index
+			shard_index: u8,
+
+			/// Number of UTXOs in the Shard
+			utilization: u64,
+
+			/// Maximum Number of UTXOs in the Shard
+			capacity: u64,
+		},
+
+		/// Verifying Context Updated Event
+		VerifyingContextUpdated {
+			/// Transfer Shape
+			shape: TransferShapeKind,
+
+			/// New Verifying Context Version
+			version: u32,
+		},
+
+		/// Transfer Shape Registered Event
+		TransferShapeRegistered {
+			/// Custom Transfer Shape
+			shape: TransferShapeKind,
+
+			/// Verifying Context Version
+			version: u32,
+
+			/// Minimum Weight of a Post
+			weight: Weight,
+		},
+
+		/// Transfer Shape Deregistered Event
+		TransferShapeDeregistered {
+			/// Custom Transfer Shape
+			shape: TransferShapeKind,
+		},
+
+		/// UTXO Accumulator Model Updated Event
+		UtxoAccumulatorModelUpdated {
+			/// New UTXO Accumulator Model Version
+			version: u32,
+		},
+
+		/// To Private Approved Event
+		ToPrivateApproved {
+			/// Owner of the Public Assets
+			owner: T::AccountId,
+
+			/// Account Approved to Convert the Public Assets
+			spender: T::AccountId,
+
+			/// Approved Asset
+			asset: Asset,
+		},
+
+		/// Shielding Policy Updated Event
+		ShieldingPolicyUpdated {
+			/// Asset Id
+			asset_id: AssetId,
+
+			/// New Shielding Policy
+			///
+			/// This is `None` if the shielding policy was removed.
+			policy: Option<ShieldingPolicy>,
+		},
+
+		/// Operation Frozen Event
+		OperationFrozen {
+			/// Frozen Ledger Operation
+			operation: LedgerOperation,
+
+			/// Asset Id
+			///
+			/// This is `None` if the operation was frozen for every asset.
+			asset_id: Option<AssetId>,
+		},
+
+		/// Operation Unfrozen Event
+		OperationUnfrozen {
+			/// Unfrozen Ledger Operation
+			operation: LedgerOperation,
+
+			/// Asset Id
+			///
+			/// This is `None` if the operation was unfrozen for every asset.
+			asset_id: Option<AssetId>,
 		},
 	}
 
@@ -364,34 +1295,136 @@ pub mod pallet {
 		/// [`CannotCreate`](FungibleLedgerError::CannotCreate) from [`FungibleLedgerError`]
 		PublicUpdateCannotCreate,
 
-		/// [`UnknownAsset`](FungibleLedgerError::UnknownAsset) from [`FungibleLedgerError`]
-		PublicUpdateUnknownAsset,
+		/// [`UnknownAsset`](FungibleLedgerError::UnknownAsset) from [`FungibleLedgerError`]
+		PublicUpdateUnknownAsset,
+
+		/// [`Overflow`](FungibleLedgerError::Overflow) from [`FungibleLedgerError`]
+		PublicUpdateOverflow,
+
+		/// [`Underflow`](FungibleLedgerError::Underflow) from [`FungibleLedgerError`]
+		PublicUpdateUnderflow,
+
+		/// [`Frozen`](FungibleLedgerError::Frozen) from [`FungibleLedgerError`]
+		PublicUpdateFrozen,
+
+		/// [`ReducedToZero`](FungibleLedgerError::ReducedToZero) from [`FungibleLedgerError`]
+		PublicUpdateReducedToZero,
+
+		/// [`NoFunds`](FungibleLedgerError::NoFunds) from [`FungibleLedgerError`]
+		PublicUpdateNoFunds,
+
+		/// [`WouldDie`](FungibleLedgerError::WouldDie) from [`FungibleLedgerError`]
+		PublicUpdateWouldDie,
+
+		/// [`InvalidTransfer`](FungibleLedgerError::InvalidTransfer) from [`FungibleLedgerError`]
+		PublicUpdateInvalidTransfer,
+
+		/// Internal Ledger Error
+		///
+		/// This is caused by some internal error in the ledger and should never occur.
+		InternalLedgerError,
+
+		/// Invalid Parameters
+		///
+		/// The submitted parameters could not be decoded.
+		InvalidParameters,
+
+		/// Stale Parameters Version
+		///
+		/// The submitted parameters do not have a greater version than the registered ones.
+		StaleParametersVersion,
+
+		/// Ledger Not Empty
+		///
+		/// The UTXO accumulator model cannot be replaced once UTXOs have been registered.
+		LedgerNotEmpty,
+
+		/// Invalid Relayer Fee
+		///
+		/// The relayer fee is greater than the reclaimed value.
+		InvalidRelayerFee,
+
+		/// Shard Capacity Exceeded
+		///
+		/// The shard Merkle tree of at least one of the UTXOs in this transfer is full.
+		ShardCapacityExceeded,
+
+		/// Asset Not Shieldable
+		///
+		/// The shielding policy of the asset does not allow converting it into a private asset.
+		AssetNotShieldable,
+
+		/// Mint Value Too Small
+		///
+		/// The value being converted into a private asset is below the minimum of its shielding
+		/// policy.
+		MintValueTooSmall,
+
+		/// Shielded Supply Cap Exceeded
+		///
+		/// The shielded supply of the asset would exceed the maximum of its shielding policy.
+		ShieldedSupplyCapExceeded,
+
+		/// Insufficient Approval
+		///
+		/// The source account has not approved the origin to convert this much of its public
+		/// assets into private ones.
+		InsufficientApproval,
 
-		/// [`Overflow`](FungibleLedgerError::Overflow) from [`FungibleLedgerError`]
-		PublicUpdateOverflow,
+		/// Invalid Deposit
+		///
+		/// The post shielding a deposit is not a mint of exactly the deposited asset.
+		InvalidDeposit,
 
-		/// [`Underflow`](FungibleLedgerError::Underflow) from [`FungibleLedgerError`]
-		PublicUpdateUnderflow,
+		/// Unknown Transfer Shape
+		///
+		/// The transfer has a custom shape which has not been registered.
+		UnknownTransferShape,
 
-		/// [`Frozen`](FungibleLedgerError::Frozen) from [`FungibleLedgerError`]
-		PublicUpdateFrozen,
+		/// Mint Frozen
+		///
+		/// Converting this asset into private assets has been frozen.
+		MintFrozen,
 
-		/// [`ReducedToZero`](FungibleLedgerError::ReducedToZero) from [`FungibleLedgerError`]
-		PublicUpdateReducedToZero,
+		/// Private Transfer Frozen
+		///
+		/// Private transfers have been frozen.
+		PrivateTransferFrozen,
 
-		/// [`NoFunds`](FungibleLedgerError::NoFunds) from [`FungibleLedgerError`]
-		PublicUpdateNoFunds,
+		/// Reclaim Frozen
+		///
+		/// Converting private assets of this asset into public ones has been frozen.
+		ReclaimFrozen,
 
-		/// [`WouldDie`](FungibleLedgerError::WouldDie) from [`FungibleLedgerError`]
-		PublicUpdateWouldDie,
+		/// Invalid Freeze
+		///
+		/// Private transfers do not reveal their asset, so they can only be frozen for all assets.
+		InvalidFreeze,
 
-		/// [`InvalidTransfer`](FungibleLedgerError::InvalidTransfer) from [`FungibleLedgerError`]
-		PublicUpdateInvalidTransfer,
+		/// Invalid Transfer Post Layout
+		///
+		/// The proof or a ciphertext of the post does not have the length registered for its
+		/// shape.
+		InvalidTransferPostLayout,
 
-		/// Internal Ledger Error
+		/// Unsupported Transfer Post Layout
 		///
-		/// This is caused by some internal error in the ledger and should never occur.
-		InternalLedgerError,
+		/// The proof system of the pallet cannot verify posts with the given layout.
+		UnsupportedTransferPostLayout,
+	}
+
+	impl<T> From<LedgerOperation> for Error<T>
+	where
+		T: Config,
+	{
+		#[inline]
+		fn from(operation: LedgerOperation) -> Self {
+			match operation {
+				LedgerOperation::Mint => Self::MintFrozen,
+				LedgerOperation::PrivateTransfer => Self::PrivateTransferFrozen,
+				LedgerOperation::Reclaim => Self::ReclaimFrozen,
+			}
+		}
 	}
 
 	impl<T> From<InvalidSourceAccount<T::AccountId>> for Error<T>
@@ -482,17 +1515,643 @@ pub mod pallet {
 	{
 		/// Returns the ledger implementation for this pallet.
 		#[inline]
-		fn ledger() -> Ledger<T> {
-			Ledger(PhantomData)
+		pub(crate) fn ledger() -> Ledger<T> {
+			Ledger {
+				void_numbers: Vec::new(),
+				registrations: Vec::new(),
+				frozen: Cell::new(None),
+				error: None,
+				__: PhantomData,
+			}
 		}
 
 		/// The account ID of AssetManager
 		pub fn account_id() -> T::AccountId {
 			T::PalletId::get().into_account()
 		}
+
+		/// Maximum Number of UTXOs in a Shard
+		pub const SHARD_CAPACITY: u64 =
+			1 << (<config::MerkleTreeConfiguration as merkle_tree::Configuration>::HEIGHT - 1);
+
+		/// Posts `post` to the ledger with the given `sources` and `sinks` accounts, returning the
+		/// event to deposit using `origin` for private transfers along with the actual weight of
+		/// the post, given that `weight` is the weight of the extrinsic for `post`.
+		///
+		/// The event carries the void numbers spent and the UTXOs registered by `post`.
+		#[inline]
+		fn post_transaction(
+			origin: Option<T::AccountId>,
+			sources: Vec<T::AccountId>,
+			sinks: Vec<T::AccountId>,
+			post: TransferPost,
+			weight: Weight,
+		) -> Result<(Event<T>, Weight), Error<T>> {
+			let weight = Self::transfer_weight(&post, weight);
+			ensure!(
+				TransferShapeKind::of(&post).map_or(false, Self::is_registered_shape),
+				Error::<T>::UnknownTransferShape
+			);
+			Self::check_shard_capacity(post.receiver_posts.iter().map(|post| &post.utxo))?;
+			let shielded = match (post.asset_id, post.sources.as_slice()) {
+				(Some(asset_id), [value]) => Some(Asset::new(asset_id, *value)),
+				_ => None,
+			};
+			let post = config::TransferPost::try_from(post)
+				.map_err(|_| Error::<T>::InvalidSerializedForm)?;
+			Self::post_decoded_transaction(origin, sources, sinks, shielded, post, weight)
+		}
+
+		/// Posts the versioned `post` to the ledger for [`transfer`](Self::transfer), using
+		/// `origin` as the account of its sources and sinks, or as the signer of its event if it
+		/// has none.
+		#[inline]
+		fn post_versioned_transaction(
+			origin: T::AccountId,
+			post: VersionedTransferPost,
+		) -> Result<(Event<T>, Weight), Error<T>> {
+			let weight = Self::versioned_transfer_weight(&post);
+			let shape = TransferShapeKind::of_versioned(&post)
+				.filter(|shape| Self::is_registered_shape(*shape))
+				.ok_or(Error::<T>::UnknownTransferShape)?;
+			ensure!(
+				post.has_layout(&Self::transfer_post_layout(shape)),
+				Error::<T>::InvalidTransferPostLayout
+			);
+			Self::check_shard_capacity(post.utxos())?;
+			let shielded = match (post.asset_id(), post.sources()) {
+				(Some(asset_id), [value]) => Some(Asset::new(asset_id, *value)),
+				_ => None,
+			};
+			let sources = vec![origin.clone(); post.sources().len()];
+			let sinks = vec![origin.clone(); post.sinks().len()];
+			let post = config::TransferPost::try_from(post)
+				.map_err(|_| Error::<T>::InvalidSerializedForm)?;
+			Self::post_decoded_transaction(
+				shape.is_private().then(|| origin),
+				sources,
+				sinks,
+				shielded,
+				post,
+				weight,
+			)
+		}
+
+		/// Posts the decoded `post` to the ledger with the given `sources` and `sinks` accounts,
+		/// checking the shielding policy of the `shielded` asset, for
+		/// [`post_transaction`](Self::post_transaction) and
+		/// [`post_versioned_transaction`](Self::post_versioned_transaction).
+		#[inline]
+		fn post_decoded_transaction(
+			origin: Option<T::AccountId>,
+			sources: Vec<T::AccountId>,
+			sinks: Vec<T::AccountId>,
+			shielded: Option<Asset>,
+			post: config::TransferPost,
+			weight: Weight,
+		) -> Result<(Event<T>, Weight), Error<T>> {
+			if let Some(asset) = shielded {
+				Self::check_shielding_policy(asset)?;
+			}
+			let mut ledger = Self::ledger();
+			let event = post
+				.post(sources, sinks, &(), &mut ledger)
+				.map_err(|err| match ledger.frozen.get() {
+					Some(operation) => Error::<T>::from(operation),
+					_ => Error::<T>::from(err),
+				})?;
+			if let Some(error) = ledger.error.take() {
+				return Err(error);
+			}
+			let weight = Self::actual_post_weight(weight, &ledger.registrations);
+			Ok((
+				event.convert(origin, ledger.void_numbers, ledger.registrations),
+				weight,
+			))
+		}
+
+		/// Returns the weight of [`to_public_remote`](Self::to_public_remote), which is its
+		/// benchmarked weight along with the weight of the heaviest [`Config::RemoteTransfer`],
+		/// since the benchmark only reaches a single destination.
+		#[inline]
+		pub fn to_public_remote_weight() -> Weight {
+			T::WeightInfo::to_public_remote().saturating_add(T::RemoteTransfer::weight())
+		}
+
+		/// Returns the actual weight of a post declared with `weight` which registered
+		/// `registrations`.
+		///
+		/// Declared weights assume that every UTXO is registered into its own shard, so the
+		/// per-shard slope of [`WeightInfo::register_utxos`] is refunded for every UTXO which
+		/// shares a shard with another one.
+		#[inline]
+		pub(crate) fn actual_post_weight(
+			weight: Weight,
+			registrations: &[UtxoRegistration],
+		) -> Weight {
+			let shards = registrations
+				.iter()
+				.map(|registration| registration.shard_index)
+				.collect::<BTreeSet<_>>()
+				.len();
+			let shared = registrations.len().saturating_sub(shards) as Weight;
+			weight.saturating_sub(Self::shard_registration_weight().saturating_mul(shared))
+		}
+
+		/// Returns the weight of registering UTXOs into one more shard, which is the slope of
+		/// [`WeightInfo::register_utxos`].
+		#[inline]
+		pub(crate) fn shard_registration_weight() -> Weight {
+			T::WeightInfo::register_utxos(1).saturating_sub(T::WeightInfo::register_utxos(0))
+		}
+
+		/// Checks that `post` is a mint of exactly `asset`, so that it can shield `asset` once it is
+		/// deposited into the [`account_id`](Self::account_id) of the pallet.
+		#[inline]
+		pub fn check_to_private_from_deposit(
+			post: &TransferPost,
+			asset: Asset,
+		) -> Result<(), Error<T>> {
+			match (
+				TransferShapeKind::of(post),
+				post.asset_id,
+				post.sources.as_slice(),
+			) {
+				(Some(TransferShapeKind::Mint), Some(asset_id), [value])
+					if asset_id == asset.id && *value == asset.value =>
+				{
+					Ok(())
+				}
+				_ => Err(Error::<T>::InvalidDeposit),
+			}
+		}
+
+		/// Transforms `asset`, which was just deposited into the [`account_id`] of the pallet, into
+		/// a private asset using the mint `post`.
+		///
+		/// # Note
+		///
+		/// The caller is responsible for depositing `asset` into the [`account_id`] of the pallet
+		/// and for reverting that deposit if this function fails. This is used by the runtime to
+		/// shield assets received over XCM, so they never land in a public account of the sender.
+		///
+		/// [`account_id`]: Self::account_id
+		#[inline]
+		pub fn to_private_from_deposit(post: TransferPost, asset: Asset) -> Result<(), Error<T>> {
+			Self::check_to_private_from_deposit(&post, asset)?;
+			let (event, _) = Self::post_transaction(
+				None,
+				vec![Self::account_id()],
+				vec![],
+				post,
+				T::WeightInfo::to_private(),
+			)?;
+			Self::deposit_event(event);
+			Ok(())
+		}
+
+		/// Returns `true` if `operation` is frozen for every asset, or for the asset with the given
+		/// `asset_id`.
+		#[inline]
+		pub fn is_frozen(operation: LedgerOperation, asset_id: Option<AssetId>) -> bool {
+			FrozenOperations::<T>::contains_key(operation, None::<AssetId>)
+				|| asset_id.map_or(false, |asset_id| {
+					FrozenOperations::<T>::contains_key(operation, Some(asset_id))
+				})
+		}
+
+		/// Checks that shielding `asset` is allowed by its [`ShieldingPolicy`], if it has one.
+		///
+		/// # Note
+		///
+		/// This is checked before `asset` is moved into the pallet account, so the
+		/// [`ShieldedDeposits`] must still have room for its value.
+		#[inline]
+		pub(crate) fn check_shielding_policy(asset: Asset) -> Result<(), Error<T>> {
+			let policy = match ShieldingPolicies::<T>::get(asset.id) {
+				Some(policy) => policy,
+				_ => return Ok(()),
+			};
+			ensure!(policy.is_shieldable, Error::<T>::AssetNotShieldable);
+			ensure!(
+				asset.value >= policy.min_mint_value,
+				Error::<T>::MintValueTooSmall
+			);
+			ensure!(
+				ShieldedDeposits::<T>::get(asset.id)
+					.checked_add(asset.value)
+					.map_or(false, |supply| supply <= policy.max_shielded_supply),
+				Error::<T>::ShieldedSupplyCapExceeded
+			);
+			Ok(())
+		}
+
+		/// Checks that the shards of `utxos` have enough room left to register them.
+		#[inline]
+		pub(crate) fn check_shard_capacity<'u, I>(utxos: I) -> Result<(), Error<T>>
+		where
+			I: IntoIterator<Item = &'u Utxo>,
+		{
+			let mut insertions = BTreeMap::<u8, u64>::new();
+			for utxo in utxos {
+				let utxo = decode::<config::Utxo, 32>(*utxo)
+					.map_err(|_| Error::<T>::InvalidSerializedForm)?;
+				*insertions
+					.entry(config::MerkleTreeConfiguration::tree_index(&utxo))
+					.or_default() += 1;
+			}
+			for (shard_index, count) in insertions {
+				ensure!(
+					ShardUtilization::<T>::get(shard_index).saturating_add(count)
+						<= Self::SHARD_CAPACITY,
+					Error::<T>::ShardCapacityExceeded
+				);
+			}
+			Ok(())
+		}
+
+		/// Emits a warning event if the utilization of the shard at `shard_index` went past the
+		/// configured threshold when growing from `previous` to `current`.
+		#[inline]
+		pub(crate) fn check_shard_utilization(shard_index: u8, previous: u64, current: u64) {
+			let threshold = T::ShardUtilizationThreshold::get().mul_ceil(Self::SHARD_CAPACITY);
+			if previous < threshold && current >= threshold {
+				Self::deposit_event(Event::ShardUtilizationThresholdReached {
+					shard_index,
+					utilization: current,
+					capacity: Self::SHARD_CAPACITY,
+				});
+			}
+		}
+
+		/// Transaction Tag Prefix for Void Numbers
+		///
+		/// This prefix is shared by unsigned private transfers and the
+		/// [`CheckVoidNumbers`](extension::CheckVoidNumbers) signed extension, so conflicting spends
+		/// are deduplicated in the transaction pool whether they are signed or not.
+		pub const VOID_NUMBER_TAG_PREFIX: &'static str = "MantaPayVoidNumber";
+
+		/// Checks that `post` has the shape of a private transfer or of a registered custom shape
+		/// without public participants, that it only spends fresh void
+		/// numbers, that its accumulator outputs have not expired and that its shards have enough
+		/// room left, returning its void numbers.
+		///
+		/// These checks are cheap compared to proof verification so they are run first.
+		#[inline]
+		pub(crate) fn check_unsigned_private_transfer(
+			post: &TransferPost,
+		) -> Result<Vec<VoidNumber>, InvalidTransaction> {
+			ensure!(
+				matches!(
+					TransferShapeKind::of(post),
+					Some(shape) if shape.is_private() && Self::is_registered_shape(shape)
+				),
+				InvalidTransaction::Call
+			);
+			let mut void_numbers = Vec::with_capacity(post.sender_posts.len());
+			for sender_post in &post.sender_posts {
+				ensure!(
+					!VoidNumberSet::<T>::contains_key(sender_post.void_number)
+						&& !void_numbers.contains(&sender_post.void_number),
+					InvalidTransaction::Stale
+				);
+				ensure!(
+					Self::has_utxo_accumulator_output(&sender_post.utxo_accumulator_output),
+					InvalidTransaction::Stale
+				);
+				void_numbers.push(sender_post.void_number);
+			}
+			Self::check_shard_capacity(post.receiver_posts.iter().map(|post| &post.utxo))
+				.map_err(|_| InvalidTransaction::ExhaustsResources)?;
+			Ok(void_numbers)
+		}
+
+		/// Returns `true` if `output` is a Utxo accumulator output which has not expired.
+		#[inline]
+		pub(crate) fn has_utxo_accumulator_output(output: &[u8; 32]) -> bool {
+			UtxoAccumulatorOutputs::<T>::contains_key(output)
+				&& !ExpiredUtxoAccumulatorOutputs::<T>::contains_key(output)
+		}
+
+		/// Records `output` as the latest Utxo accumulator output of the shard at `shard_index`,
+		/// expiring the oldest output of that shard once its window is full.
+		#[inline]
+		pub(crate) fn push_utxo_accumulator_output(shard_index: u8, output: [u8; 32]) {
+			let window = T::UtxoAccumulatorOutputWindow::get().max(1);
+			let head = UtxoAccumulatorOutputRingHead::<T>::get(shard_index);
+			if let Some(expired) = UtxoAccumulatorOutputRing::<T>::get(shard_index, head) {
+				ExpiredUtxoAccumulatorOutputs::<T>::insert(expired, ());
+			}
+			UtxoAccumulatorOutputRing::<T>::insert(shard_index, head, output);
+			UtxoAccumulatorOutputRingHead::<T>::insert(shard_index, (head + 1) % window);
+			UtxoAccumulatorOutputs::<T>::insert(output, frame_system::Pallet::<T>::block_number());
+		}
+
+		/// Resizes the Utxo accumulator output ring buffer of every shard to the current
+		/// [`Config::UtxoAccumulatorOutputWindow`] if the window changed since the last resize,
+		/// keeping the most recent outputs of each shard and expiring the older ones.
+		pub(crate) fn resize_utxo_accumulator_output_rings() -> Weight {
+			let window = T::UtxoAccumulatorOutputWindow::get().max(1);
+			if UtxoAccumulatorOutputRingWindow::<T>::get() == Some(window) {
+				return T::DbWeight::get().reads(1);
+			}
+			let heads = UtxoAccumulatorOutputRingHead::<T>::iter().collect::<Vec<_>>();
+			let mut slots: Weight = 0;
+			for (shard_index, head) in &heads {
+				let mut ring =
+					UtxoAccumulatorOutputRing::<T>::drain_prefix(shard_index).collect::<Vec<_>>();
+				slots += ring.len() as Weight;
+				ring.sort_by_key(|(slot, _)| (slot < head, *slot));
+				let expired = ring.len().saturating_sub(window as usize);
+				for (_, output) in &ring[..expired] {
+					ExpiredUtxoAccumulatorOutputs::<T>::insert(output, ());
+				}
+				for (slot, (_, output)) in ring[expired..].iter().enumerate() {
+					UtxoAccumulatorOutputRing::<T>::insert(shard_index, slot as u32, output);
+				}
+				UtxoAccumulatorOutputRingHead::<T>::insert(
+					shard_index,
+					(ring.len() - expired) as u32 % window,
+				);
+			}
+			UtxoAccumulatorOutputRingWindow::<T>::put(window);
+			let shards = heads.len() as Weight;
+			T::DbWeight::get().reads_writes(1 + shards + slots, 1 + shards + 2 * slots)
+		}
+
+		/// Returns `true` if posts of the given `shape` are accepted, which is always the case for
+		/// the canonical shapes and only after registration for custom ones.
+		#[inline]
+		pub fn is_registered_shape(shape: TransferShapeKind) -> bool {
+			!matches!(shape, TransferShapeKind::Custom { .. })
+				|| CustomTransferShapes::<T>::contains_key(shape)
+		}
+
+		/// Returns the weight of posting `post`, which is `default` unless `post` has a registered
+		/// custom shape with a greater weight.
+		#[inline]
+		pub fn transfer_weight(post: &TransferPost, default: Weight) -> Weight {
+			Self::shape_weight(TransferShapeKind::of(post), default)
+		}
+
+		/// Returns the weight of posting the versioned `post` with [`transfer`], which is the
+		/// weight of the matching version 1 call along with the overhead of the versioned
+		/// encoding measured by benchmarking [`transfer`] on a version 2 private transfer.
+		///
+		/// [`transfer`]: Self::transfer
+		#[inline]
+		pub fn versioned_transfer_weight(post: &VersionedTransferPost) -> Weight {
+			let default = if !post.sources().is_empty() {
+				T::WeightInfo::to_private()
+			} else if !post.sinks().is_empty() {
+				T::WeightInfo::to_public()
+			} else {
+				T::WeightInfo::private_transfer()
+			};
+			Self::shape_weight(TransferShapeKind::of_versioned(post), default).saturating_add(
+				T::WeightInfo::transfer().saturating_sub(T::WeightInfo::private_transfer()),
+			)
+		}
+
+		/// Returns the weight of posting a post of the given `shape`, which is `default` unless
+		/// `shape` is a registered custom shape with a greater weight.
+		#[inline]
+		fn shape_weight(shape: Option<TransferShapeKind>, default: Weight) -> Weight {
+			match shape {
+				Some(shape @ TransferShapeKind::Custom { .. }) => {
+					CustomTransferShapes::<T>::get(shape)
+						.map_or(default, |weight| weight.max(default))
+				}
+				_ => default,
+			}
+		}
+
+		/// Returns the serialized verifying context for transfers of the given `shape`, falling
+		/// back to the built-in testnet verifying context if none has been registered.
+		///
+		/// Custom shapes have no built-in verifying context, so the returned context is empty
+		/// until they are registered.
+		#[inline]
+		pub fn verifying_context(shape: TransferShapeKind) -> Cow<'static, [u8]> {
+			match VerifyingContexts::<T>::get(shape) {
+				Some(parameters) => Cow::Owned(parameters.bytes),
+				None if matches!(shape, TransferShapeKind::Custom { .. }) => Cow::Borrowed(&[]),
+				None => Cow::Borrowed(
+					match shape {
+						TransferShapeKind::Mint => manta_sdk::pay::testnet::verifying::Mint::get(),
+						TransferShapeKind::PrivateTransfer => {
+							manta_sdk::pay::testnet::verifying::PrivateTransfer::get()
+						}
+						TransferShapeKind::Reclaim => {
+							manta_sdk::pay::testnet::verifying::Reclaim::get()
+						}
+						TransferShapeKind::Custom { .. } => unreachable!(),
+					}
+					.expect("Checksum did not match."),
+				),
+			}
+		}
+
+		/// Returns the layout of the [`TransferPostV2`] posts of the given `shape`, falling back to
+		/// [`TransferPostLayout::V1`] if none has been registered.
+		#[inline]
+		pub fn transfer_post_layout(shape: TransferShapeKind) -> TransferPostLayout {
+			TransferPostLayouts::<T>::get(shape).unwrap_or_default()
+		}
+
+		/// Returns the versions of the [`VersionedTransferPost`] posts accepted by
+		/// [`transfer`](Self::transfer).
+		#[inline]
+		pub fn supported_transfer_post_versions() -> Vec<u8> {
+			VersionedTransferPost::SUPPORTED_VERSIONS.to_vec()
+		}
+
+		/// Returns the layouts which can be registered for a verifying context.
+		#[inline]
+		pub fn supported_transfer_post_layouts() -> Vec<TransferPostLayout> {
+			TransferPostLayout::SUPPORTED.to_vec()
+		}
+
+		/// Returns the serialized UTXO accumulator model, falling back to the built-in testnet
+		/// model if none has been registered.
+		#[inline]
+		pub fn utxo_accumulator_model() -> Cow<'static, [u8]> {
+			match UtxoAccumulatorModelParameters::<T>::get() {
+				Some(parameters) => Cow::Owned(parameters.bytes),
+				None => Cow::Borrowed(
+					manta_sdk::pay::testnet::parameters::UtxoAccumulatorModel::get()
+						.expect("Checksum did not match."),
+				),
+			}
+		}
+
+		/// Registers `parameters` as the verifying context for transfers of the given `shape`,
+		/// along with the `layout` of the posts they verify.
+		#[inline]
+		fn insert_verifying_context(
+			shape: TransferShapeKind,
+			parameters: &VersionedParameters,
+			layout: Option<TransferPostLayout>,
+		) {
+			VerifyingContexts::<T>::insert(shape, parameters);
+			TransferPostLayouts::<T>::set(shape, layout);
+		}
+
+		/// Registers `parameters` as the UTXO accumulator model.
+		#[inline]
+		fn put_utxo_accumulator_model(parameters: &VersionedParameters) {
+			UtxoAccumulatorModelParameters::<T>::put(parameters);
+		}
+
+		/// Returns the decoded verifying context for transfers of the given `shape`.
+		#[inline]
+		pub(crate) fn decoded_verifying_context(
+			shape: TransferShapeKind,
+		) -> Option<config::VerifyingContext> {
+			Self::decode_verifying_context(&Self::verifying_context(shape))
+		}
+
+		/// Returns the decoded UTXO accumulator model.
+		#[inline]
+		pub(crate) fn decoded_utxo_accumulator_model() -> Option<config::UtxoAccumulatorModel> {
+			Self::decode_utxo_accumulator_model(&Self::utxo_accumulator_model())
+		}
+
+		/// Decodes a verifying context from `bytes`.
+		#[inline]
+		pub(crate) fn decode_verifying_context(bytes: &[u8]) -> Option<config::VerifyingContext> {
+			config::VerifyingContext::decode(bytes).ok()
+		}
+
+		/// Decodes a UTXO accumulator model from `bytes`.
+		#[inline]
+		pub(crate) fn decode_utxo_accumulator_model(
+			bytes: &[u8],
+		) -> Option<config::UtxoAccumulatorModel> {
+			config::UtxoAccumulatorModel::decode(bytes).ok()
+		}
+
+		/// Maximum Number of Receivers Returned by a Single Pull
+		pub const PULL_MAX_RECEIVER_UPDATE_SIZE: usize = 1024;
+
+		/// Maximum Number of Senders Returned by a Single Pull
+		pub const PULL_MAX_SENDER_UPDATE_SIZE: usize = 1024;
+
+		/// Pulls receiver data from the ledger starting at the per-shard indices in `checkpoint`,
+		/// returning `true` in the first component if the page filled up before all shards were
+		/// exhausted.
+		#[inline]
+		fn pull_receivers(checkpoint: &Checkpoint) -> (bool, ReceiverChunk) {
+			let mut receivers = Vec::new();
+			for shard_index in 0..=u8::MAX {
+				let mut leaf_index = checkpoint.receiver_index(shard_index);
+				while let Ok(entry) = Shards::<T>::try_get(shard_index, leaf_index) {
+					if receivers.len() == Self::PULL_MAX_RECEIVER_UPDATE_SIZE {
+						return (true, receivers);
+					}
+					receivers.push(entry);
+					leaf_index += 1;
+				}
+			}
+			(false, receivers)
+		}
+
+		/// Pulls sender data from the ledger starting at `sender_index`, returning `true` in the
+		/// first component if the page filled up before all void numbers were returned.
+		#[inline]
+		fn pull_senders(sender_index: u64) -> (bool, SenderChunk) {
+			let mut senders = Vec::new();
+			let mut index = sender_index;
+			while let Ok(void_number) = VoidNumberSetInsertionOrder::<T>::try_get(index) {
+				if senders.len() == Self::PULL_MAX_SENDER_UPDATE_SIZE {
+					return (true, senders);
+				}
+				senders.push(void_number);
+				index += 1;
+			}
+			(false, senders)
+		}
+
+		/// Returns the diff of ledger state since the given `checkpoint`.
+		#[inline]
+		pub fn pull_ledger_diff(checkpoint: Checkpoint) -> PullResponse {
+			let (more_receivers, receivers) = Self::pull_receivers(&checkpoint);
+			let (more_senders, senders) = Self::pull_senders(checkpoint.sender_index);
+			PullResponse {
+				should_continue: more_receivers || more_senders,
+				receivers,
+				senders,
+			}
+		}
+	}
+}
+
+/// Shielded Fee Handler
+pub trait OnShieldedFee<AccountId> {
+	/// Handles the fee `asset` paid out of a shielded balance, which is currently held by the
+	/// `payer` account.
+	fn on_shielded_fee(payer: &AccountId, asset: Asset) -> Result<(), FungibleLedgerError>;
+}
+
+impl<AccountId> OnShieldedFee<AccountId> for () {
+	#[inline]
+	fn on_shielded_fee(payer: &AccountId, asset: Asset) -> Result<(), FungibleLedgerError> {
+		let _ = (payer, asset);
+		Ok(())
 	}
 }
 
+/// Remote Transfer Handler
+pub trait RemoteTransfer<AccountId> {
+	/// Destination Type
+	type Destination: Clone + Debug + Decode + Encode + Eq + PartialEq + TypeInfo;
+
+	/// Sends `asset` held by the `source` account to `destination` on another chain, buying
+	/// `destination_weight` of execution there.
+	fn transfer(
+		source: AccountId,
+		asset: Asset,
+		destination: Self::Destination,
+		destination_weight: Weight,
+	) -> DispatchResult;
+
+	/// Returns the local weight of [`transfer`](Self::transfer), including the XCM it executes.
+	fn weight() -> Weight;
+
+	/// Returns a destination which [`transfer`](Self::transfer) can reach, for benchmarking.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_destination() -> Self::Destination;
+}
+
+impl<AccountId> RemoteTransfer<AccountId> for () {
+	type Destination = ();
+
+	#[inline]
+	fn transfer(
+		source: AccountId,
+		asset: Asset,
+		destination: Self::Destination,
+		destination_weight: Weight,
+	) -> DispatchResult {
+		let _ = (source, asset, destination, destination_weight);
+		Err(DispatchError::Other("Remote transfers are not supported."))
+	}
+
+	#[inline]
+	fn weight() -> Weight {
+		0
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	#[inline]
+	fn benchmark_destination() -> Self::Destination {}
+}
+
+/// Remote Destination Type
+pub type RemoteDestination<T> = <<T as Config>::RemoteTransfer as RemoteTransfer<
+	<T as frame_system::Config>::AccountId,
+>>::Destination;
+
 /// Preprocessed Event
 enum PreprocessedEvent<T>
 where
@@ -525,24 +2184,75 @@ where
 	T: Config,
 {
 	/// Converts a [`PreprocessedEvent`] with into an [`Event`] using the given `origin` for
-	/// [`PreprocessedEvent::PrivateTransfer`].
+	/// [`PreprocessedEvent::PrivateTransfer`], and the `void_numbers` and `registrations` recorded
+	/// by the [`Ledger`] while posting.
 	#[inline]
-	fn convert(self, origin: Option<T::AccountId>) -> Event<T> {
+	fn convert(
+		self,
+		origin: Option<T::AccountId>,
+		void_numbers: Vec<VoidNumber>,
+		registrations: Vec<UtxoRegistration>,
+	) -> Event<T> {
 		match self {
-			Self::ToPrivate { asset, source } => Event::ToPrivate { asset, source },
+			Self::ToPrivate { asset, source } => Event::ToPrivate {
+				asset,
+				source,
+				registrations,
+			},
 			Self::PrivateTransfer => Event::PrivateTransfer {
-				// FIXME: get rid of unwrap eventually.
-				origin: origin.unwrap(),
+				origin,
+				void_numbers,
+				registrations,
+			},
+			Self::ToPublic { asset, sink } => Event::ToPublic {
+				asset,
+				sink,
+				void_numbers,
+				registrations,
 			},
-			Self::ToPublic { asset, sink } => Event::ToPublic { asset, sink },
 		}
 	}
 }
 
 /// Ledger
-struct Ledger<T>(PhantomData<T>)
+///
+/// The void numbers spent and the UTXOs registered while posting are recorded in the ledger so
+/// that they can be reported in the [`Event`] for the post.
+struct Ledger<T>
+where
+	T: Config,
+{
+	/// Spent Void Numbers
+	void_numbers: Vec<VoidNumber>,
+
+	/// Registered UTXOs
+	registrations: Vec<UtxoRegistration>,
+
+	/// Frozen Operation which Rejected the Post
+	frozen: Cell<Option<LedgerOperation>>,
+
+	/// Error which Aborted the Registration of the UTXOs
+	error: Option<Error<T>>,
+
+	/// Type Parameter Marker
+	__: PhantomData<T>,
+}
+
+impl<T> Ledger<T>
 where
-	T: Config;
+	T: Config,
+{
+	/// Returns `true` if `operation` is frozen for the asset with the given `asset_id`, recording
+	/// it so that the post fails with the matching error.
+	#[inline]
+	fn is_frozen(&self, operation: LedgerOperation, asset_id: Option<AssetId>) -> bool {
+		let frozen = Pallet::<T>::is_frozen(operation, asset_id);
+		if frozen {
+			self.frozen.set(Some(operation));
+		}
+		frozen
+	}
+}
 
 /// Wrap Type
 #[derive(Clone, Copy)]
@@ -588,7 +2298,7 @@ where
 		&self,
 		output: config::UtxoAccumulatorOutput,
 	) -> Option<Self::ValidUtxoAccumulatorOutput> {
-		if UtxoAccumulatorOutputs::<T>::contains_key(encode(&output)) {
+		if Pallet::<T>::has_utxo_accumulator_output(&encode(&output)) {
 			return Some(Wrap(output));
 		}
 		None
@@ -600,16 +2310,14 @@ where
 		I: IntoIterator<Item = (Self::ValidUtxoAccumulatorOutput, Self::ValidVoidNumber)>,
 	{
 		let _ = super_key;
-		let index = VoidNumberSetSize::<T>::get();
-		let mut i = 0;
 		for (_, void_number) in iter {
 			let void_number = encode(&void_number.0);
 			VoidNumberSet::<T>::insert(void_number, ());
-			VoidNumberSetInsertionOrder::<T>::insert(index + i, void_number);
-			i += 1;
-		}
-		if i != 0 {
-			VoidNumberSetSize::<T>::set(index + i);
+			VoidNumberSetInsertionOrder::<T>::insert(
+				VoidNumberSetInsertionOrder::<T>::count() as u64,
+				void_number,
+			);
+			self.void_numbers.push(void_number);
 		}
 	}
 }
@@ -636,11 +2344,13 @@ where
 		I: IntoIterator<Item = (Self::ValidUtxo, config::EncryptedNote)>,
 	{
 		let _ = super_key;
-		let parameters = config::UtxoAccumulatorModel::decode(
-			manta_sdk::pay::testnet::parameters::UtxoAccumulatorModel::get()
-				.expect("Checksum did not match."),
-		)
-		.expect("Unable to decode the Merkle Tree Parameters.");
+		let parameters = match Pallet::<T>::decoded_utxo_accumulator_model() {
+			Some(parameters) => parameters,
+			_ => {
+				self.error = Some(Error::<T>::InternalLedgerError);
+				return;
+			}
+		};
 		let mut shard_indices = iter
 			.into_iter()
 			.map(move |(utxo, note)| {
@@ -663,25 +2373,47 @@ where
 			let mut tree = ShardTrees::<T>::get(shard_index);
 			let mut next_root = Option::<config::UtxoAccumulatorOutput>::None;
 			let mut current_path = core::mem::take(&mut tree.current_path).into();
+			let mut utilization = ShardUtilization::<T>::get(shard_index);
+			let previous_utilization = utilization;
 			for (utxo, note) in insertions {
-				next_root = Some(
-					merkle_tree::single_path::raw::insert(
-						&parameters,
-						&mut tree.leaf_digest,
-						&mut current_path,
-						utxo,
-					)
-					.expect("If this errors, then we have run out of Merkle Tree capacity."),
-				);
+				match merkle_tree::single_path::raw::insert(
+					&parameters,
+					&mut tree.leaf_digest,
+					&mut current_path,
+					utxo,
+				) {
+					Some(root) => next_root = Some(root),
+					None => {
+						// NOTE: Shard capacity is checked by `Pallet::check_shard_capacity` before
+						//       posting and by the genesis build, so this should never happen. If
+						//       it does, the post fails instead of dropping the UTXO.
+						self.error = Some(Error::<T>::ShardCapacityExceeded);
+						return;
+					}
+				}
 				let next_index = current_path.leaf_index().0 as u64;
 				let utxo = encode(&utxo);
+				let note = EncryptedNote::from(note);
 				UtxoSet::<T>::insert(utxo, ());
-				Shards::<T>::insert(shard_index, next_index, (utxo, EncryptedNote::from(note)));
+				Shards::<T>::insert(shard_index, next_index, (utxo, note.clone()));
+				self.registrations.push(UtxoRegistration {
+					utxo,
+					note,
+					shard_index,
+					leaf_index: next_index,
+				});
+				utilization += 1;
 			}
 			tree.current_path = current_path.into();
 			if let Some(next_root) = next_root {
 				ShardTrees::<T>::insert(shard_index, tree);
-				UtxoAccumulatorOutputs::<T>::insert(encode(&next_root), ());
+				ShardUtilization::<T>::insert(shard_index, utilization);
+				Pallet::<T>::push_utxo_accumulator_output(shard_index, encode(&next_root));
+				Pallet::<T>::check_shard_utilization(
+					shard_index,
+					previous_utilization,
+					utilization,
+				);
 			}
 		}
 	}
@@ -710,6 +2442,13 @@ where
 	{
 		sources
 			.map(move |(account_id, withdraw)| {
+				if self.is_frozen(LedgerOperation::Mint, Some(asset_id.0)) {
+					return Err(InvalidSourceAccount {
+						account_id,
+						asset_id,
+						withdraw,
+					});
+				}
 				FungibleLedger::<T>::can_withdraw(asset_id.0, &account_id, withdraw.0)
 					.map(|_| WrapPair(account_id.clone(), withdraw))
 					.map_err(|_| InvalidSourceAccount {
@@ -734,6 +2473,13 @@ where
 		//		 pass the data forward.
 		sinks
 			.map(move |(account_id, deposit)| {
+				if self.is_frozen(LedgerOperation::Reclaim, Some(asset_id.0)) {
+					return Err(InvalidSinkAccount {
+						account_id,
+						asset_id,
+						deposit,
+					});
+				}
 				FungibleLedger::<T>::can_deposit(asset_id.0, &account_id, deposit.0)
 					.map(|_| WrapPair(account_id.clone(), deposit))
 					.map_err(|_| InvalidSinkAccount {
@@ -755,37 +2501,37 @@ where
 		sinks: &[SinkPostingKey<config::Config, Self>],
 		proof: Proof<config::Config>,
 	) -> Option<(Self::ValidProof, Self::Event)> {
-		let (mut verifying_context, event) = match TransferShape::select(
+		let shape = TransferShapeKind::select(
 			asset_id.is_some(),
 			sources.len(),
 			senders.len(),
 			receivers.len(),
 			sinks.len(),
-		)? {
-			TransferShape::Mint => (
-				manta_sdk::pay::testnet::verifying::Mint::get().expect("Checksum did not match."),
-				PreprocessedEvent::<T>::ToPrivate {
-					asset: Asset::new(asset_id.unwrap().0, (sources[0].1).0),
-					source: sources[0].0.clone(),
-				},
-			),
-			TransferShape::PrivateTransfer => (
-				manta_sdk::pay::testnet::verifying::PrivateTransfer::get()
-					.expect("Checksum did not match."),
-				PreprocessedEvent::<T>::PrivateTransfer,
-			),
-			TransferShape::Reclaim => (
-				manta_sdk::pay::testnet::verifying::Reclaim::get()
-					.expect("Checksum did not match."),
-				PreprocessedEvent::<T>::ToPublic {
-					asset: Asset::new(asset_id.unwrap().0, (sinks[0].1).0),
-					sink: sinks[0].0.clone(),
-				},
-			),
+		)?;
+		if !Pallet::<T>::is_registered_shape(shape) {
+			return None;
+		}
+		let event = match (asset_id, sources, sinks) {
+			(Some(asset_id), [source], []) => PreprocessedEvent::<T>::ToPrivate {
+				asset: Asset::new(asset_id.0, (source.1).0),
+				source: source.0.clone(),
+			},
+			(Some(asset_id), [], [sink]) => PreprocessedEvent::<T>::ToPublic {
+				asset: Asset::new(asset_id.0, (sink.1).0),
+				sink: sink.0.clone(),
+			},
+			_ => PreprocessedEvent::<T>::PrivateTransfer,
 		};
+		let operation = match event {
+			PreprocessedEvent::ToPrivate { .. } => LedgerOperation::Mint,
+			PreprocessedEvent::PrivateTransfer => LedgerOperation::PrivateTransfer,
+			PreprocessedEvent::ToPublic { .. } => LedgerOperation::Reclaim,
+		};
+		if self.is_frozen(operation, asset_id.map(|asset_id| asset_id.0)) {
+			return None;
+		}
 		config::ProofSystem::verify(
-			&config::VerifyingContext::decode(&mut verifying_context)
-				.expect("Unable to decode the verifying context."),
+			&Pallet::<T>::decoded_verifying_context(shape)?,
 			&TransferPostingKey::generate_proof_input(asset_id, sources, senders, receivers, sinks),
 			&proof,
 		)
@@ -810,6 +2556,9 @@ where
 				&Pallet::<T>::account_id(),
 				withdraw.0,
 			)?;
+			ShieldedDeposits::<T>::mutate(asset_id.0, |deposits| {
+				*deposits = deposits.saturating_add(withdraw.0)
+			});
 		}
 		for WrapPair(account_id, deposit) in sinks {
 			FungibleLedger::<T>::transfer(
@@ -818,6 +2567,9 @@ where
 				&account_id,
 				deposit.0,
 			)?;
+			ShieldedDeposits::<T>::mutate(asset_id.0, |deposits| {
+				*deposits = deposits.saturating_sub(deposit.0)
+			});
 		}
 		Ok(())
 	}
@@ -0,0 +1,379 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of pallet-manta-pay.
+//
+// pallet-manta-pay is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// pallet-manta-pay is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage Migrations
+
+use super::*;
+use frame_support::{
+	dispatch::GetStorageVersion,
+	pallet_prelude::Weight,
+	storage::migration,
+	traits::{Get, PalletInfoAccess, StorageVersion},
+};
+
+impl<T> Pallet<T>
+where
+	T: Config,
+{
+	/// Maximum Number of Shard Leaves Replayed in each Block by the V0->V1 Migration
+	pub const SHARD_REPLAY_STEP: u32 = 64;
+
+	/// Migrates the pallet from storage version `0` to `1`.
+	///
+	/// Recovering the order in which the Utxo accumulator outputs of each shard were created
+	/// requires replaying the insertions into the shard, which is too heavy for a single block.
+	/// Instead, the replay is scheduled here and advanced by [`replay_shards`] in every
+	/// `on_initialize`. Until its shard is replayed, every existing output stays valid.
+	///
+	/// [`replay_shards`]: Self::replay_shards
+	pub fn migrate_v0_to_v1() -> Weight {
+		if Self::on_chain_storage_version() < 1 {
+			log::info!("Executing manta-pay V0->V1 migration!");
+			UtxoAccumulatorOutputRingWindow::<T>::put(T::UtxoAccumulatorOutputWindow::get().max(1));
+			ShardReplay::<T>::put(ShardReplayCursor::default());
+			log::info!(" >>> Scheduled the replay of the shards");
+			StorageVersion::new(1).put::<Self>();
+			T::DbWeight::get().reads_writes(1, 3)
+		} else {
+			log::debug!("manta-pay V0->V1 migration not needed!");
+			0
+		}
+	}
+
+	/// Replays up to `step` leaves of the shards from the [`ShardReplay`] cursor, if any.
+	///
+	/// The replayed outputs of each shard which are still stored are kept up to the window, and
+	/// the older ones are expired. Once a shard is fully replayed, its ring buffer is seeded with
+	/// the kept outputs.
+	pub(crate) fn replay_shards(step: u32) -> Weight {
+		let mut cursor = match ShardReplay::<T>::get() {
+			Some(cursor) => cursor,
+			_ => return T::DbWeight::get().reads(1),
+		};
+		let parameters = match Self::decoded_utxo_accumulator_model() {
+			Some(parameters) => parameters,
+			_ => {
+				log::error!("Unable to decode the Merkle Tree Parameters to replay the shards.");
+				return T::DbWeight::get().reads(2);
+			}
+		};
+		let window = T::UtxoAccumulatorOutputWindow::get().max(1) as usize;
+		let mut weight: Weight = 0;
+		let mut leaves = 0;
+		while leaves < step {
+			leaves += 1;
+			if let Ok((utxo, _)) = Shards::<T>::try_get(cursor.shard_index, cursor.leaf_index) {
+				match Self::replay_leaf(&parameters, &mut cursor.tree, utxo) {
+					Some(root) => {
+						cursor.leaf_index += 1;
+						if UtxoAccumulatorOutputs::<T>::contains_key(root) {
+							if cursor.outputs.len() == window {
+								let expired = cursor.outputs.remove(0);
+								ExpiredUtxoAccumulatorOutputs::<T>::insert(expired, ());
+							}
+							cursor.outputs.push(root);
+						}
+						continue;
+					}
+					_ => log::error!(
+						"Unable to replay leaf {} of manta-pay shard {}.",
+						cursor.leaf_index,
+						cursor.shard_index
+					),
+				}
+			}
+			weight = weight.saturating_add(Self::seed_utxo_accumulator_output_ring(
+				cursor.shard_index,
+				core::mem::take(&mut cursor.outputs),
+			));
+			match cursor.shard_index.checked_add(1) {
+				Some(shard_index) => {
+					cursor = ShardReplayCursor {
+						shard_index,
+						..Default::default()
+					}
+				}
+				_ => {
+					ShardReplay::<T>::kill();
+					log::info!("manta-pay V0->V1 shard replay completed!");
+					return weight.saturating_add(T::WeightInfo::replay_shards(leaves));
+				}
+			}
+		}
+		ShardReplay::<T>::put(cursor);
+		weight.saturating_add(T::WeightInfo::replay_shards(leaves))
+	}
+
+	/// Inserts `utxo` into the shard `tree`, returning the new root.
+	#[inline]
+	pub(crate) fn replay_leaf(
+		parameters: &config::UtxoAccumulatorModel,
+		tree: &mut UtxoMerkleTreePath,
+		utxo: [u8; 32],
+	) -> Option<[u8; 32]> {
+		let utxo = decode::<config::Utxo, 32>(utxo).ok()?;
+		let mut current_path = core::mem::take(&mut tree.current_path).into();
+		let root = merkle_tree::single_path::raw::insert(
+			parameters,
+			&mut tree.leaf_digest,
+			&mut current_path,
+			utxo,
+		);
+		tree.current_path = current_path.into();
+		root.map(|root| encode(&root))
+	}
+
+	/// Seeds the Utxo accumulator output ring buffer of the shard at `shard_index` with its
+	/// replayed `outputs`, followed by the outputs pushed to it since the migration, keeping the
+	/// most recent ones up to the window and expiring the others.
+	fn seed_utxo_accumulator_output_ring(shard_index: u8, mut outputs: Vec<[u8; 32]>) -> Weight {
+		let window = T::UtxoAccumulatorOutputWindow::get().max(1);
+		let head = UtxoAccumulatorOutputRingHead::<T>::get(shard_index);
+		let mut ring =
+			UtxoAccumulatorOutputRing::<T>::drain_prefix(shard_index).collect::<Vec<_>>();
+		ring.sort_by_key(|(slot, _)| (*slot < head, *slot));
+		let pushed = ring.len() as Weight;
+		outputs.retain(|output| ring.iter().all(|(_, newer)| newer != output));
+		let replayed = outputs.len();
+		outputs.extend(ring.into_iter().map(|(_, output)| output));
+		if outputs.is_empty() {
+			return T::DbWeight::get().reads(2);
+		}
+		let expired = outputs.len().saturating_sub(window as usize);
+		for output in &outputs[..expired] {
+			ExpiredUtxoAccumulatorOutputs::<T>::insert(output, ());
+		}
+		let now = frame_system::Pallet::<T>::block_number();
+		for output in outputs.iter().take(replayed).skip(expired) {
+			UtxoAccumulatorOutputs::<T>::insert(output, now);
+		}
+		for (slot, output) in outputs[expired..].iter().enumerate() {
+			UtxoAccumulatorOutputRing::<T>::insert(shard_index, slot as u32, output);
+		}
+		UtxoAccumulatorOutputRingHead::<T>::insert(
+			shard_index,
+			(outputs.len() - expired) as u32 % window,
+		);
+		T::DbWeight::get().reads_writes(2 + pushed, 1 + pushed + 2 * outputs.len() as Weight)
+	}
+
+	/// Checks that the V0->V1 migration applies.
+	pub fn pre_migrate_v0_to_v1() -> Result<(), &'static str> {
+		if Self::on_chain_storage_version() >= 1 {
+			return Err("Migration to V1 does not apply");
+		}
+		Ok(())
+	}
+
+	/// Checks that the V0->V1 migration was applied.
+	pub fn post_migrate_v0_to_v1() -> Result<(), &'static str> {
+		if Self::on_chain_storage_version() < 1 {
+			return Err("storage version not upgraded");
+		}
+		if UtxoAccumulatorOutputRingWindow::<T>::get().is_none() {
+			return Err("ring buffer window was not initialized");
+		}
+		if !ShardReplay::<T>::exists() {
+			return Err("shard replay was not scheduled");
+		}
+		Ok(())
+	}
+}
+
+impl<T> Pallet<T>
+where
+	T: Config,
+{
+	/// Migrates the pallet from storage version `1` to `2`.
+	///
+	/// The utilization of every shard is computed from the leaf index of its current path.
+	pub fn migrate_v1_to_v2() -> Weight {
+		if Self::on_chain_storage_version() == 1 {
+			log::info!("Executing manta-pay V1->V2 migration!");
+			let mut shards: Weight = 0;
+			for (shard_index, tree) in ShardTrees::<T>::iter() {
+				if tree.leaf_digest.is_some() {
+					let utilization =
+						merkle_tree::CurrentPath::<config::MerkleTreeConfiguration>::from(
+							tree.current_path,
+						)
+						.leaf_index()
+						.0 as u64 + 1;
+					ShardUtilization::<T>::insert(shard_index, utilization);
+					Self::check_shard_utilization(shard_index, 0, utilization);
+				}
+				shards += 1;
+			}
+			log::info!(" >>> Computed the utilization of {} shards", shards);
+			StorageVersion::new(2).put::<Self>();
+			T::DbWeight::get().reads_writes(1 + shards, 1 + shards)
+		} else {
+			log::debug!("manta-pay V1->V2 migration not needed!");
+			0
+		}
+	}
+
+	/// Checks that the V1->V2 migration applies.
+	pub fn pre_migrate_v1_to_v2() -> Result<(), &'static str> {
+		if Self::on_chain_storage_version() >= 2 {
+			return Err("Migration to V2 does not apply");
+		}
+		Ok(())
+	}
+
+	/// Checks that the V1->V2 migration was applied.
+	pub fn post_migrate_v1_to_v2() -> Result<(), &'static str> {
+		if Self::on_chain_storage_version() < 2 {
+			return Err("storage version not upgraded");
+		}
+		for (shard_index, tree) in ShardTrees::<T>::iter() {
+			if tree.leaf_digest.is_some()
+				&& !Shards::<T>::contains_key(
+					shard_index,
+					ShardUtilization::<T>::get(shard_index).saturating_sub(1),
+				) {
+				return Err("shard utilization does not match the shard");
+			}
+		}
+		Ok(())
+	}
+}
+
+impl<T> Pallet<T>
+where
+	T: Config,
+{
+	/// Returns the `VoidNumberSetSize` value removed in storage version `3`, if it is still stored.
+	#[inline]
+	fn legacy_void_number_set_size() -> Option<u64> {
+		migration::get_storage_value::<u64>(
+			<Self as PalletInfoAccess>::name().as_bytes(),
+			b"VoidNumberSetSize",
+			&[],
+		)
+	}
+
+	/// Migrates the pallet from storage version `2` to `3`.
+	///
+	/// The counter of the [`VoidNumberSetInsertionOrder`] is initialized from its entries, and the
+	/// redundant `VoidNumberSetSize` value is removed. The insertion order entries themselves are
+	/// left untouched, so wallets reading them keep working.
+	pub fn migrate_v2_to_v3() -> Weight {
+		if Self::on_chain_storage_version() == 2 {
+			log::info!("Executing manta-pay V2->V3 migration!");
+			let count = VoidNumberSetInsertionOrder::<T>::initialize_counter();
+			log::info!(" >>> Counted {} void numbers", count);
+			let size = migration::take_storage_value::<u64>(
+				<Self as PalletInfoAccess>::name().as_bytes(),
+				b"VoidNumberSetSize",
+				&[],
+			);
+			if let Some(size) = size.filter(|size| *size != count as u64) {
+				log::warn!(
+					" >>> Void number set size {} does not match its insertion order",
+					size
+				);
+			}
+			StorageVersion::new(3).put::<Self>();
+			T::DbWeight::get().reads_writes(2 + count as Weight, 3)
+		} else {
+			log::debug!("manta-pay V2->V3 migration not needed!");
+			0
+		}
+	}
+
+	/// Checks that the V2->V3 migration applies.
+	pub fn pre_migrate_v2_to_v3() -> Result<(), &'static str> {
+		if Self::on_chain_storage_version() >= 3 {
+			return Err("Migration to V3 does not apply");
+		}
+		Ok(())
+	}
+
+	/// Checks that the V2->V3 migration was applied.
+	pub fn post_migrate_v2_to_v3() -> Result<(), &'static str> {
+		if Self::on_chain_storage_version() < 3 {
+			return Err("storage version not upgraded");
+		}
+		if Self::legacy_void_number_set_size().is_some() {
+			return Err("void number set size was not removed");
+		}
+		let count = VoidNumberSetInsertionOrder::<T>::count();
+		if VoidNumberSetInsertionOrder::<T>::iter_keys().count() as u32 != count {
+			return Err("void number set counter does not match its insertion order");
+		}
+		if count != 0 && !VoidNumberSetInsertionOrder::<T>::contains_key(count as u64 - 1) {
+			return Err("void number set insertion order is not contiguous");
+		}
+		Ok(())
+	}
+}
+
+impl<T> Pallet<T>
+where
+	T: Config,
+{
+	/// Migrates the pallet from storage version `3` to `4`.
+	///
+	/// The [`ShieldedDeposits`] of every asset in `asset_ids` are seeded from the balance of the
+	/// pallet account, since they were not counted before. Public assets sent to the pallet
+	/// account in any other way cannot be told apart from shielded ones, so they are counted too.
+	pub fn migrate_v3_to_v4<I>(asset_ids: I) -> Weight
+	where
+		I: IntoIterator<Item = AssetId>,
+	{
+		if Self::on_chain_storage_version() == 3 {
+			log::info!("Executing manta-pay V3->V4 migration!");
+			let account = Self::account_id();
+			let (mut assets, mut deposits): (Weight, Weight) = (0, 0);
+			for asset_id in asset_ids {
+				if let Ok(balance) = FungibleLedger::<T>::balance(asset_id, &account) {
+					if balance > 0 {
+						ShieldedDeposits::<T>::insert(asset_id, balance);
+						deposits += 1;
+					}
+				}
+				assets += 1;
+			}
+			log::info!(
+				" >>> Seeded the shielded deposits of {} out of {} assets",
+				deposits,
+				assets
+			);
+			StorageVersion::new(4).put::<Self>();
+			T::DbWeight::get().reads_writes(1 + 2 * assets, 1 + deposits)
+		} else {
+			log::debug!("manta-pay V3->V4 migration not needed!");
+			0
+		}
+	}
+
+	/// Checks that the V3->V4 migration applies.
+	pub fn pre_migrate_v3_to_v4() -> Result<(), &'static str> {
+		if Self::on_chain_storage_version() >= 4 {
+			return Err("Migration to V4 does not apply");
+		}
+		Ok(())
+	}
+
+	/// Checks that the V3->V4 migration was applied.
+	pub fn post_migrate_v3_to_v4() -> Result<(), &'static str> {
+		if Self::on_chain_storage_version() < 4 {
+			return Err("storage version not upgraded");
+		}
+		Self::check_deposit_invariants()
+	}
+}
@@ -15,15 +15,17 @@
 // along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
 
 use frame_support::{
+	dispatch::DispatchResult,
 	parameter_types,
 	traits::{ConstU32, Everything},
+	weights::{constants::RocksDbWeight, Weight},
 	PalletId,
 };
 use frame_system::EnsureRoot;
 use manta_primitives::{
 	assets::{
 		AssetConfig, AssetLocation, AssetRegistrar, AssetRegistrarMetadata, AssetStorageMetadata,
-		ConcreteFungibleLedger,
+		ConcreteFungibleLedger, FungibleLedger, FungibleLedgerError,
 	},
 	constants::{ASSET_MANAGER_PALLET_ID, MANTA_PAY_PALLET_ID},
 	types::{AssetId, Balance},
@@ -32,7 +34,8 @@ use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
-	AccountId32,
+	transaction_validity::{TransactionLongevity, TransactionPriority},
+	AccountId32, DispatchError, Percent,
 };
 use xcm::{
 	prelude::{Parachain, X1},
@@ -50,7 +53,7 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		MantaPayPallet: crate::{Pallet, Call, Storage, Event<T>},
+		MantaPayPallet: crate::{Pallet, Call, Storage, Event<T>, Config, ValidateUnsigned},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Assets: pallet_assets::{Pallet, Storage, Event<T>},
 		AssetManager: pallet_asset_manager::{Pallet, Call, Storage, Event<T>},
@@ -68,7 +71,7 @@ impl frame_system::Config for Test {
 	type BaseCallFilter = Everything;
 	type BlockWeights = ();
 	type BlockLength = ();
-	type DbWeight = ();
+	type DbWeight = RocksDbWeight;
 	type Origin = Origin;
 	type Call = Call;
 	type Index = u64;
@@ -230,6 +233,10 @@ impl pallet_asset_manager::Config for Test {
 
 parameter_types! {
 	pub const MantaPayPalletId: PalletId = MANTA_PAY_PALLET_ID;
+	pub const MantaPayUnsignedPriority: TransactionPriority = TransactionPriority::MIN;
+	pub const MantaPayUnsignedLongevity: TransactionLongevity = 16;
+	pub const MantaPayShardUtilizationThreshold: Percent = Percent::from_percent(80);
+	pub const MantaPayUtxoAccumulatorOutputWindow: u32 = 16;
 }
 
 impl crate::Config for Test {
@@ -237,6 +244,35 @@ impl crate::Config for Test {
 	type WeightInfo = crate::weights::SubstrateWeight<Self>;
 	type PalletId = MantaPayPalletId;
 	type AssetConfig = MantaAssetConfig;
+	type ParametersOrigin = EnsureRoot<AccountId32>;
+	type FreezeOrigin = EnsureRoot<AccountId32>;
+	type UtxoAccumulatorOutputWindow = MantaPayUtxoAccumulatorOutputWindow;
+	type ShardUtilizationThreshold = MantaPayShardUtilizationThreshold;
+	type UnsignedPriority = MantaPayUnsignedPriority;
+	type UnsignedLongevity = MantaPayUnsignedLongevity;
+	type OnShieldedFee = MockShieldedFees;
+	type RemoteTransfer = MockRemoteTransfer;
+}
+
+/// Fee Collector Account
+pub const FEE_COLLECTOR: AccountId32 = AccountId32::new([255; 32]);
+
+/// Shielded Fee Handler which sends every fee to [`FEE_COLLECTOR`]
+pub struct MockShieldedFees;
+
+impl crate::OnShieldedFee<AccountId32> for MockShieldedFees {
+	#[inline]
+	fn on_shielded_fee(
+		payer: &AccountId32,
+		asset: crate::Asset,
+	) -> Result<(), FungibleLedgerError> {
+		<MantaAssetConfig as AssetConfig<Test>>::FungibleLedger::transfer(
+			asset.id,
+			payer,
+			&FEE_COLLECTOR,
+			asset.value,
+		)
+	}
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -245,3 +281,45 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		.unwrap()
 		.into()
 }
+
+/// Unreachable Remote Account
+pub const UNREACHABLE: AccountId32 = AccountId32::new([254; 32]);
+
+/// Remote Transfer Handler which models remote accounts as local ones, failing to reach
+/// [`UNREACHABLE`]
+pub struct MockRemoteTransfer;
+
+impl crate::RemoteTransfer<AccountId32> for MockRemoteTransfer {
+	type Destination = AccountId32;
+
+	#[inline]
+	fn transfer(
+		source: AccountId32,
+		asset: crate::Asset,
+		destination: Self::Destination,
+		destination_weight: Weight,
+	) -> DispatchResult {
+		let _ = destination_weight;
+		if destination == UNREACHABLE {
+			return Err(DispatchError::Other("Unreachable destination."));
+		}
+		<MantaAssetConfig as AssetConfig<Test>>::FungibleLedger::transfer(
+			asset.id,
+			&source,
+			&destination,
+			asset.value,
+		)
+		.map_err(|_| DispatchError::Other("Unable to send the asset."))
+	}
+
+	#[inline]
+	fn weight() -> Weight {
+		0
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	#[inline]
+	fn benchmark_destination() -> Self::Destination {
+		AccountId32::new([1; 32])
+	}
+}
@@ -0,0 +1,86 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of pallet-manta-pay.
+//
+// pallet-manta-pay is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// pallet-manta-pay is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
+
+//! MantaPay RPC Interfaces
+
+use crate::{
+	runtime::MantaPayPullApi,
+	types::{Checkpoint, PullResponse},
+};
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+/// Pull API
+#[rpc]
+pub trait PullApi<BlockHash> {
+	/// Returns the update required to be synchronized with the ledger starting from
+	/// `checkpoint`, read at block `at` or at the best block if `at` is not given.
+	#[rpc(name = "mantaPay_pullLedgerDiff")]
+	fn pull_ledger_diff(
+		&self,
+		checkpoint: Checkpoint,
+		at: Option<BlockHash>,
+	) -> Result<PullResponse>;
+}
+
+/// Pull RPC API Implementation
+pub struct Pull<C, B> {
+	/// Client
+	client: Arc<C>,
+
+	/// Type Parameter Marker
+	__: PhantomData<B>,
+}
+
+impl<C, B> Pull<C, B> {
+	/// Builds a new [`Pull`] RPC API implementation.
+	#[inline]
+	pub fn new(client: Arc<C>) -> Self {
+		Self {
+			client,
+			__: PhantomData,
+		}
+	}
+}
+
+impl<C, B> PullApi<<B as BlockT>::Hash> for Pull<C, B>
+where
+	B: BlockT,
+	C: 'static + ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync,
+	C::Api: MantaPayPullApi<B>,
+{
+	#[inline]
+	fn pull_ledger_diff(
+		&self,
+		checkpoint: Checkpoint,
+		at: Option<<B as BlockT>::Hash>,
+	) -> Result<PullResponse> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		self.client
+			.runtime_api()
+			.pull_ledger_diff(&at, checkpoint)
+			.map_err(|err| Error {
+				code: ErrorCode::ServerError(1),
+				message: "Unable to compute state diff for pull.".into(),
+				data: Some(err.to_string().into()),
+			})
+	}
+}
@@ -0,0 +1,42 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of pallet-manta-pay.
+//
+// pallet-manta-pay is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// pallet-manta-pay is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
+
+//! MantaPay Runtime APIs
+
+use crate::types::{Checkpoint, PullResponse, TransferPostLayout, TransferShapeKind};
+use alloc::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// MantaPay Pull API
+	pub trait MantaPayPullApi {
+		/// Returns the update required to be synchronized with the ledger starting from
+		/// `checkpoint`.
+		fn pull_ledger_diff(checkpoint: Checkpoint) -> PullResponse;
+	}
+
+	/// MantaPay Transfer Post API
+	pub trait MantaPayTransferPostApi {
+		/// Returns the layout of the version 2 transfer posts accepted for transfers of the given
+		/// `shape`.
+		fn transfer_post_layout(shape: TransferShapeKind) -> TransferPostLayout;
+
+		/// Returns the versions of the transfer posts accepted by the `transfer` call.
+		fn supported_transfer_post_versions() -> Vec<u8>;
+
+		/// Returns the layouts which can be registered for a verifying context.
+		fn supported_transfer_post_layouts() -> Vec<TransferPostLayout>;
+	}
+}
@@ -15,4 +15,5 @@
 // along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
 
 mod payment;
+mod simulation;
 mod storage;
@@ -15,17 +15,37 @@
 // along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	mock::{new_test_ext, MantaAssetConfig, MantaAssetRegistrar, MantaPayPallet, Origin, Test},
-	Error, FungibleLedger,
+	extension::CheckVoidNumbers,
+	mock::{
+		new_test_ext, Assets, Event, MantaAssetConfig, MantaAssetRegistrar, MantaPayPallet,
+		MantaPayShardUtilizationThreshold, MantaPayUnsignedLongevity, MantaPayUnsignedPriority,
+		MantaPayUtxoAccumulatorOutputWindow, Origin, System, Test, FEE_COLLECTOR, UNREACHABLE,
+	},
+	types::{
+		Checkpoint, ShieldingPolicy, TransferPostLayout, TransferPostV2, TransferShapeKind,
+		VersionedParameters, VersionedPrecomputedCoins, VersionedTransferPost,
+	},
+	Error, FungibleLedger, GenesisConfig,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{GenesisBuild, Get, Hooks, StorageVersion},
+};
+use sp_runtime::{
+	traits::{SignedExtension, ValidateUnsigned},
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	DispatchError,
 };
-use frame_support::{assert_noop, assert_ok};
 use manta_accounting::{
 	asset::{Asset, AssetId, AssetValue},
-	transfer::{self, test::value_distribution, SpendingKey},
+	transfer::{self, test::value_distribution, ReceiverLedger, SpendingKey},
 };
 use manta_crypto::{
 	accumulator::Accumulator,
-	merkle_tree::{forest::TreeArrayMerkleForest, full::Full},
+	merkle_tree::{
+		forest::{Configuration as _, TreeArrayMerkleForest},
+		full::Full,
+	},
 	rand::{CryptoRng, Rand, RngCore, Sample},
 };
 use manta_pay::config::{
@@ -39,51 +59,114 @@ use manta_primitives::{
 };
 use manta_util::codec::{Decode, IoReader};
 use rand::thread_rng;
-use std::fs::File;
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	env,
+	fs::File,
+	path::{Path, PathBuf},
+};
 
 /// UTXO Accumulator for Building Circuits
-type UtxoAccumulator =
+pub(crate) type UtxoAccumulator =
 	TreeArrayMerkleForest<MerkleTreeConfiguration, Full<MerkleTreeConfiguration>, 256>;
 
 lazy_static::lazy_static! {
-	static ref PROVING_CONTEXT: MultiProvingContext = load_proving_context();
-	static ref PARAMETERS: Parameters = load_parameters();
-	static ref UTXO_ACCUMULATOR_MODEL: UtxoAccumulatorModel = load_utxo_accumulator_model();
+	pub(crate) static ref PROVING_CONTEXT: Option<MultiProvingContext> = load_proving_context();
+	pub(crate) static ref PARAMETERS: Parameters = load_parameters();
+	pub(crate) static ref UTXO_ACCUMULATOR_MODEL: UtxoAccumulatorModel =
+		load_utxo_accumulator_model();
+}
+
+/// Returns early from the calling test, with a message, if the proving contexts are not available.
+macro_rules! require_proving_context {
+	() => {
+		if $crate::test::payment::PROVING_CONTEXT.is_none() {
+			eprintln!("Skipping the test: the MantaPay proving contexts are not available.");
+			return;
+		}
+	};
 }
 
+pub(crate) use require_proving_context;
+
 pub const ALICE: sp_runtime::AccountId32 = sp_runtime::AccountId32::new([0u8; 32]);
+pub const RELAYER: sp_runtime::AccountId32 = sp_runtime::AccountId32::new([1u8; 32]);
+pub const BOB: sp_runtime::AccountId32 = sp_runtime::AccountId32::new([2u8; 32]);
 pub const NATIVE_ASSET_ID: AssetId =
 	AssetId(<MantaAssetConfig as AssetConfig<Test>>::NativeAssetId::get());
 
-/// Loads the [`MultiProvingContext`] from the SDK.
+/// Loads the [`MultiProvingContext`] from `MANTA_PAY_PARAMETERS_DIR`, or from the `parameters`
+/// directory of this crate, returning `None` if it is not available.
+///
+/// With the `download` feature, missing proving contexts are first downloaded from the SDK.
 #[inline]
-fn load_proving_context() -> MultiProvingContext {
-	let directory = tempfile::tempdir().expect("Unable to create temporary directory.");
-	let path = directory.path();
-	let mint_path = path.join("mint.dat");
-	manta_sdk::pay::testnet::proving::Mint::download(&mint_path)
-		.expect("Unable to download MINT proving context.");
-	let private_transfer_path = path.join("private-transfer.dat");
-	manta_sdk::pay::testnet::proving::PrivateTransfer::download(&private_transfer_path)
-		.expect("Unable to download PRIVATE_TRANSFER proving context.");
-	let reclaim_path = path.join("reclaim.dat");
-	manta_sdk::pay::testnet::proving::Reclaim::download(&reclaim_path)
-		.expect("Unable to download RECLAIM proving context.");
-	MultiProvingContext {
-		mint: ProvingContext::decode(IoReader(
-			File::open(mint_path).expect("Unable to open MINT proving context file."),
-		))
-		.expect("Unable to decode MINT proving context."),
-		private_transfer: ProvingContext::decode(IoReader(
-			File::open(private_transfer_path)
-				.expect("Unable to open PRIVATE_TRANSFER proving context file."),
-		))
-		.expect("Unable to decode PRIVATE_TRANSFER proving context."),
-		reclaim: ProvingContext::decode(IoReader(
-			File::open(reclaim_path).expect("Unable to open RECLAIM proving context file."),
-		))
-		.expect("Unable to decode RECLAIM proving context."),
+fn load_proving_context() -> Option<MultiProvingContext> {
+	let directory = env::var_os("MANTA_PAY_PARAMETERS_DIR")
+		.map(PathBuf::from)
+		.unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("parameters"));
+	let mint_path = directory.join("mint.dat");
+	let private_transfer_path = directory.join("private-transfer.dat");
+	let reclaim_path = directory.join("reclaim.dat");
+	#[cfg(feature = "download")]
+	{
+		if let Err(err) = std::fs::create_dir_all(&directory) {
+			eprintln!(
+				"Unable to create the parameters directory {:?}: {}.",
+				directory, err
+			);
+			return None;
+		}
+		if !mint_path.exists() {
+			if let Err(err) = manta_sdk::pay::testnet::proving::Mint::download(&mint_path) {
+				eprintln!("Unable to download MINT proving context: {:?}.", err);
+				return None;
+			}
+		}
+		if !private_transfer_path.exists() {
+			if let Err(err) =
+				manta_sdk::pay::testnet::proving::PrivateTransfer::download(&private_transfer_path)
+			{
+				eprintln!(
+					"Unable to download PRIVATE_TRANSFER proving context: {:?}.",
+					err
+				);
+				return None;
+			}
+		}
+		if !reclaim_path.exists() {
+			if let Err(err) = manta_sdk::pay::testnet::proving::Reclaim::download(&reclaim_path) {
+				eprintln!("Unable to download RECLAIM proving context: {:?}.", err);
+				return None;
+			}
+		}
 	}
+	let open = |path: &Path| {
+		File::open(path)
+			.map_err(|err| {
+				eprintln!(
+					"Unable to open the proving context {:?}: {}. Enable the `download` feature to \
+					 download it.",
+					path, err
+				)
+			})
+			.ok()
+	};
+	Some(MultiProvingContext {
+		mint: ProvingContext::decode(IoReader(open(&mint_path)?))
+			.expect("Unable to decode MINT proving context."),
+		private_transfer: ProvingContext::decode(IoReader(open(&private_transfer_path)?))
+			.expect("Unable to decode PRIVATE_TRANSFER proving context."),
+		reclaim: ProvingContext::decode(IoReader(open(&reclaim_path)?))
+			.expect("Unable to decode RECLAIM proving context."),
+	})
+}
+
+/// Returns the [`MultiProvingContext`] of a test starting with [`require_proving_context`].
+#[inline]
+pub(crate) fn proving_context() -> &'static MultiProvingContext {
+	PROVING_CONTEXT
+		.as_ref()
+		.expect("Tests building proofs must start with `require_proving_context!()`.")
 }
 
 /// Loads the [`Parameters`] from the SDK.
@@ -127,7 +210,7 @@ where
 	Mint::from_spending_key(&PARAMETERS, &rng.gen(), asset, rng)
 		.into_post(
 			FullParameters::new(&PARAMETERS, &UTXO_ACCUMULATOR_MODEL),
-			&PROVING_CONTEXT.mint,
+			&proving_context().mint,
 			rng,
 		)
 		.expect("Unable to build MINT proof.")
@@ -135,7 +218,7 @@ where
 
 /// Mints many assets with the given `id` and `value`.
 #[inline]
-fn mint_tokens<R>(id: AssetId, values: &[AssetValue], rng: &mut R)
+pub(crate) fn mint_tokens<R>(id: AssetId, values: &[AssetValue], rng: &mut R)
 where
 	R: CryptoRng + RngCore + ?Sized,
 {
@@ -171,7 +254,7 @@ where
 	for balance in balances {
 		let spending_key = SpendingKey::gen(rng);
 		let (mint_0, pre_sender_0) = transfer::test::sample_mint(
-			&PROVING_CONTEXT.mint,
+			&proving_context().mint,
 			FullParameters::new(&PARAMETERS, utxo_accumulator.model()),
 			&spending_key,
 			asset_id.with(balance),
@@ -186,7 +269,7 @@ where
 			.insert_and_upgrade(&mut utxo_accumulator)
 			.expect("Just inserted so this should not fail.");
 		let (mint_1, pre_sender_1) = transfer::test::sample_mint(
-			&PROVING_CONTEXT.mint,
+			&proving_context().mint,
 			FullParameters::new(&PARAMETERS, utxo_accumulator.model()),
 			&spending_key,
 			asset_id.value(0),
@@ -208,7 +291,7 @@ where
 			PrivateTransfer::build([sender_0, sender_1], [receiver_0, receiver_1])
 				.into_post(
 					FullParameters::new(&PARAMETERS, utxo_accumulator.model()),
-					&PROVING_CONTEXT.private_transfer,
+					&proving_context().private_transfer,
 					rng,
 				)
 				.unwrap();
@@ -223,6 +306,59 @@ where
 	posts
 }
 
+/// Mints the inputs of `count`-many [`PrivateTransfer`]s and returns their posts without
+/// submitting them.
+#[inline]
+fn sample_private_transfers<R>(count: usize, rng: &mut R) -> Vec<TransferPost>
+where
+	R: CryptoRng + RngCore + ?Sized,
+{
+	let asset_id = rng.gen();
+	let value = AssetValue(1_000_000);
+	initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+	let mut utxo_accumulator = UtxoAccumulator::new(UTXO_ACCUMULATOR_MODEL.clone());
+	let mut senders = Vec::new();
+	for balance in value_distribution(count, value, rng) {
+		let spending_key = SpendingKey::gen(rng);
+		let mut pair = Vec::new();
+		for asset in [asset_id.with(balance), asset_id.value(0)] {
+			let (mint, pre_sender) = transfer::test::sample_mint(
+				&proving_context().mint,
+				FullParameters::new(&PARAMETERS, utxo_accumulator.model()),
+				&spending_key,
+				asset,
+				rng,
+			)
+			.unwrap();
+			assert_ok!(MantaPayPallet::to_private(Origin::signed(ALICE), mint.into()));
+			pair.push(
+				pre_sender
+					.insert_and_upgrade(&mut utxo_accumulator)
+					.expect("Just inserted so this should not fail."),
+			);
+		}
+		senders.push((spending_key, balance, pair));
+	}
+	senders
+		.into_iter()
+		.map(|(spending_key, balance, mut pair)| {
+			let sender_1 = pair.pop().unwrap();
+			let sender_0 = pair.pop().unwrap();
+			let (receiver_0, _) =
+				spending_key.internal_pair(&PARAMETERS, rng.gen(), asset_id.value(0));
+			let (receiver_1, _) =
+				spending_key.internal_pair(&PARAMETERS, rng.gen(), asset_id.with(balance));
+			PrivateTransfer::build([sender_0, sender_1], [receiver_0, receiver_1])
+				.into_post(
+					FullParameters::new(&PARAMETERS, utxo_accumulator.model()),
+					&proving_context().private_transfer,
+					rng,
+				)
+				.unwrap()
+		})
+		.collect()
+}
+
 /// Builds `count`-many [`Reclaim`] tests.
 #[inline]
 fn reclaim_test<R>(count: usize, id_option: Option<AssetId>, rng: &mut R) -> Vec<TransferPost>
@@ -243,7 +379,7 @@ where
 	for balance in balances {
 		let spending_key = SpendingKey::gen(rng);
 		let (mint_0, pre_sender_0) = transfer::test::sample_mint(
-			&PROVING_CONTEXT.mint,
+			&proving_context().mint,
 			FullParameters::new(&PARAMETERS, utxo_accumulator.model()),
 			&spending_key,
 			asset_id.with(balance),
@@ -258,7 +394,7 @@ where
 			.insert_and_upgrade(&mut utxo_accumulator)
 			.expect("Just inserted so this should not fail.");
 		let (mint_1, pre_sender_1) = transfer::test::sample_mint(
-			&PROVING_CONTEXT.mint,
+			&proving_context().mint,
 			FullParameters::new(&PARAMETERS, utxo_accumulator.model()),
 			&spending_key,
 			asset_id.value(0),
@@ -277,7 +413,7 @@ where
 		let reclaim = Reclaim::build([sender_0, sender_1], [receiver], asset_id.with(balance))
 			.into_post(
 				FullParameters::new(&PARAMETERS, utxo_accumulator.model()),
-				&PROVING_CONTEXT.reclaim,
+				&proving_context().reclaim,
 				rng,
 			)
 			.unwrap();
@@ -291,9 +427,46 @@ where
 	posts
 }
 
+/// Mints the inputs of a [`Reclaim`] of `value` and returns its post without submitting it.
+#[inline]
+fn sample_reclaim<R>(asset_id: AssetId, value: AssetValue, rng: &mut R) -> TransferPost
+where
+	R: CryptoRng + RngCore + ?Sized,
+{
+	let mut utxo_accumulator = UtxoAccumulator::new(UTXO_ACCUMULATOR_MODEL.clone());
+	let spending_key = SpendingKey::gen(rng);
+	let mut senders = Vec::new();
+	for asset in [asset_id.with(value), asset_id.value(0)] {
+		let (mint, pre_sender) = transfer::test::sample_mint(
+			&proving_context().mint,
+			FullParameters::new(&PARAMETERS, utxo_accumulator.model()),
+			&spending_key,
+			asset,
+			rng,
+		)
+		.unwrap();
+		assert_ok!(MantaPayPallet::to_private(Origin::signed(ALICE), mint.into()));
+		senders.push(
+			pre_sender
+				.insert_and_upgrade(&mut utxo_accumulator)
+				.expect("Just inserted so this should not fail."),
+		);
+	}
+	let sender_1 = senders.pop().unwrap();
+	let sender_0 = senders.pop().unwrap();
+	let (receiver, _) = spending_key.internal_pair(&PARAMETERS, rng.gen(), asset_id.value(0));
+	Reclaim::build([sender_0, sender_1], [receiver], asset_id.with(value))
+		.into_post(
+			FullParameters::new(&PARAMETERS, utxo_accumulator.model()),
+			&proving_context().reclaim,
+			rng,
+		)
+		.unwrap()
+}
+
 /// Initializes a test by allocating `value`-many assets of the given `id` to the default account.
 #[inline]
-fn initialize_test(id: AssetId, value: AssetValue) {
+pub(crate) fn initialize_test(id: AssetId, value: AssetValue) {
 	let metadata = AssetRegistrarMetadata::default();
 	assert_ok!(MantaAssetRegistrar::create_asset(
 		id.0,
@@ -307,11 +480,65 @@ fn initialize_test(id: AssetId, value: AssetValue) {
 		&MantaPayPallet::account_id(),
 		DEFAULT_ASSET_ED
 	));
+	assert_ok!(MantaPayPallet::set_shielding_policy(
+		Origin::root(),
+		id.0,
+		Some(ShieldingPolicy::unrestricted())
+	));
+}
+
+/// Precomputed Coins generated by `src/bin/precompute_coins.rs`
+const PRECOMPUTED_COINS: &[u8] = include_bytes!("../benchmark/precomputed_coins.scale");
+
+/// Tests that every precomputed coin can be posted to an empty ledger.
+#[test]
+fn precomputed_coins_should_work() {
+	let coins = VersionedPrecomputedCoins::decode_latest(PRECOMPUTED_COINS)
+		.expect("Unable to decode the precomputed coins.");
+	let mut entries = coins
+		.mints
+		.into_iter()
+		.map(|mint| (vec![], mint))
+		.collect::<Vec<_>>();
+	entries.extend(
+		coins
+			.private_transfers
+			.into_iter()
+			.chain(coins.reclaims)
+			.map(|(inputs, post)| (inputs.to_vec(), post)),
+	);
+	assert!(!entries.is_empty());
+	for (inputs, post) in entries {
+		new_test_ext().execute_with(|| {
+			let asset_ids = inputs
+				.iter()
+				.chain([&post])
+				.filter_map(|post| post.asset_id)
+				.collect::<BTreeSet<_>>();
+			for asset_id in asset_ids {
+				initialize_test(AssetId(asset_id), AssetValue(1_000_000_000));
+			}
+			for input in inputs {
+				assert_ok!(MantaPayPallet::to_private(Origin::signed(ALICE), input));
+			}
+			if !post.sources.is_empty() {
+				assert_ok!(MantaPayPallet::to_private(Origin::signed(ALICE), post));
+			} else if !post.sinks.is_empty() {
+				assert_ok!(MantaPayPallet::to_public(Origin::signed(ALICE), post));
+			} else {
+				assert_ok!(MantaPayPallet::private_transfer(
+					Origin::signed(ALICE),
+					post
+				));
+			}
+		});
+	}
 }
 
 /// Tests multiple to_private from some total supply.
 #[test]
 fn to_private_should_work() {
+	require_proving_context!();
 	let mut rng = thread_rng();
 	new_test_ext().execute_with(|| {
 		let asset_id = rng.gen();
@@ -330,6 +557,7 @@ fn to_private_should_work() {
 
 #[test]
 fn native_asset_to_private_should_work() {
+	require_proving_context!();
 	let mut rng = thread_rng();
 	new_test_ext().execute_with(|| {
 		// FIXME: get rid of divide by two after parity fix pallet-asset
@@ -348,6 +576,7 @@ fn native_asset_to_private_should_work() {
 /// Tests a mint that would overdraw the total supply.
 #[test]
 fn overdrawn_mint_should_not_work() {
+	require_proving_context!();
 	let mut rng = thread_rng();
 	new_test_ext().execute_with(|| {
 		let asset_id = rng.gen();
@@ -370,6 +599,7 @@ fn overdrawn_mint_should_not_work() {
 /// Tests a mint that would overdraw from a non-existent supply.
 #[test]
 fn to_private_without_init_should_not_work() {
+	require_proving_context!();
 	let mut rng = thread_rng();
 	new_test_ext().execute_with(|| {
 		assert_noop!(
@@ -385,6 +615,7 @@ fn to_private_without_init_should_not_work() {
 /// Tests that a double-spent [`Mint`] will fail.
 #[test]
 fn mint_existing_coin_should_not_work() {
+	require_proving_context!();
 	let mut rng = thread_rng();
 	new_test_ext().execute_with(|| {
 		let asset_id = rng.gen();
@@ -404,12 +635,14 @@ fn mint_existing_coin_should_not_work() {
 /// Tests a [`PrivateTransfer`] transaction.
 #[test]
 fn private_transfer_should_work() {
+	require_proving_context!();
 	new_test_ext().execute_with(|| private_transfer_test(1, None, &mut thread_rng()));
 }
 
 /// Test a [`PrivateTransfer`] transaction with native currency
 #[test]
 fn private_transfer_native_asset_shoud_work() {
+	require_proving_context!();
 	new_test_ext().execute_with(|| {
 		private_transfer_test(1, Some(NATIVE_ASSET_ID), &mut thread_rng());
 	});
@@ -418,12 +651,14 @@ fn private_transfer_native_asset_shoud_work() {
 /// Tests multiple [`PrivateTransfer`] transactions.
 #[test]
 fn private_transfer_10_times_should_work() {
+	require_proving_context!();
 	new_test_ext().execute_with(|| private_transfer_test(10, None, &mut thread_rng()));
 }
 
 /// Tests that a double-spent [`PrivateTransfer`] will fail.
 #[test]
 fn double_spend_in_private_transfer_should_not_work() {
+	require_proving_context!();
 	new_test_ext().execute_with(|| {
 		for private_transfer in private_transfer_test(1, None, &mut thread_rng()) {
 			assert_noop!(
@@ -437,24 +672,28 @@ fn double_spend_in_private_transfer_should_not_work() {
 /// Tests a [`Reclaim`] transaction.
 #[test]
 fn reclaim_should_work() {
+	require_proving_context!();
 	new_test_ext().execute_with(|| reclaim_test(1, None, &mut thread_rng()));
 }
 
 /// Test a [`Reclaim`] of native currency
 #[test]
 fn reclaim_native_should_work() {
+	require_proving_context!();
 	new_test_ext().execute_with(|| reclaim_test(1, Some(NATIVE_ASSET_ID), &mut thread_rng()));
 }
 
 /// Tests multiple [`Reclaim`] transactions.
 #[test]
 fn reclaim_10_times_should_work() {
+	require_proving_context!();
 	new_test_ext().execute_with(|| reclaim_test(10, None, &mut thread_rng()));
 }
 
 /// Tests that a double-spent [`Reclaim`] will fail.
 #[test]
 fn double_spend_in_reclaim_should_not_work() {
+	require_proving_context!();
 	new_test_ext().execute_with(|| {
 		for reclaim in reclaim_test(1, None, &mut thread_rng()) {
 			assert_noop!(
@@ -464,3 +703,1647 @@ fn double_spend_in_reclaim_should_not_work() {
 		}
 	});
 }
+
+/// Tests that pulling from an empty checkpoint returns every registered UTXO and void number.
+#[test]
+fn pull_ledger_diff_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let posts = reclaim_test(2, None, &mut rng);
+		let response = MantaPayPallet::pull_ledger_diff(Checkpoint::default());
+		assert!(!response.should_continue);
+		assert_eq!(response.receivers.len(), 6);
+		assert_eq!(
+			response.senders,
+			posts
+				.iter()
+				.flat_map(|post| crate::types::TransferPost::from(post.clone()).sender_posts)
+				.map(|sender_post| sender_post.void_number)
+				.collect::<Vec<_>>()
+		);
+		let mut receiver_index = vec![0; crate::types::SHARD_COUNT];
+		for (utxo, _) in &response.receivers {
+			let utxo = crate::types::decode(*utxo).expect("Unable to decode UTXO.");
+			receiver_index[MerkleTreeConfiguration::tree_index(&utxo) as usize] += 1;
+		}
+		let response = MantaPayPallet::pull_ledger_diff(Checkpoint::new(
+			receiver_index,
+			response.senders.len() as u64,
+		));
+		assert!(!response.should_continue);
+		assert!(response.receivers.is_empty());
+		assert!(response.senders.is_empty());
+	});
+}
+
+/// Tests that governance can register a new verifying context and that it is used for verification.
+#[test]
+fn set_verifying_context_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let private_transfer = MantaPayPallet::verifying_context(TransferShapeKind::PrivateTransfer);
+		assert_ok!(MantaPayPallet::set_verifying_context(
+			Origin::root(),
+			TransferShapeKind::Mint,
+			VersionedParameters::new(1, private_transfer.into_owned()),
+			None,
+		));
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		assert_noop!(
+			MantaPayPallet::to_private(
+				Origin::signed(ALICE),
+				sample_mint(value.with(asset_id), &mut rng).into()
+			),
+			Error::<Test>::InvalidProof,
+		);
+		let mint = manta_sdk::pay::testnet::verifying::Mint::get()
+			.expect("Checksum did not match.")
+			.to_vec();
+		assert_ok!(MantaPayPallet::set_verifying_context(
+			Origin::root(),
+			TransferShapeKind::Mint,
+			VersionedParameters::new(2, mint),
+			None,
+		));
+		mint_tokens(asset_id, &[value], &mut rng);
+	});
+}
+
+/// Tests that only the parameters origin can register a verifying context.
+#[test]
+fn set_verifying_context_without_privilege_should_not_work() {
+	new_test_ext().execute_with(|| {
+		let mint = MantaPayPallet::verifying_context(TransferShapeKind::Mint);
+		assert_noop!(
+			MantaPayPallet::set_verifying_context(
+				Origin::signed(ALICE),
+				TransferShapeKind::Mint,
+				VersionedParameters::new(1, mint.into_owned()),
+				None,
+			),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+/// Tests that verifying contexts which do not decode or do not increase the version are rejected.
+#[test]
+fn set_invalid_verifying_context_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			MantaPayPallet::set_verifying_context(
+				Origin::root(),
+				TransferShapeKind::Reclaim,
+				VersionedParameters::new(1, vec![0; 8]),
+				None,
+			),
+			Error::<Test>::InvalidParameters,
+		);
+		let reclaim = MantaPayPallet::verifying_context(TransferShapeKind::Reclaim).into_owned();
+		assert_ok!(MantaPayPallet::set_verifying_context(
+			Origin::root(),
+			TransferShapeKind::Reclaim,
+			VersionedParameters::new(3, reclaim.clone()),
+			None,
+		));
+		assert_noop!(
+			MantaPayPallet::set_verifying_context(
+				Origin::root(),
+				TransferShapeKind::Reclaim,
+				VersionedParameters::new(3, reclaim),
+				None,
+			),
+			Error::<Test>::StaleParametersVersion,
+		);
+	});
+}
+
+/// Consolidation Transfer Shape
+const CONSOLIDATION: TransferShapeKind = TransferShapeKind::Custom {
+	sources: 0,
+	senders: 4,
+	receivers: 2,
+	sinks: 0,
+};
+
+/// Builds a post with the [`CONSOLIDATION`] shape out of two [`PrivateTransfer`]s, keeping the
+/// proof of the first one.
+#[inline]
+fn sample_consolidation<R>(rng: &mut R) -> crate::TransferPost
+where
+	R: CryptoRng + RngCore + ?Sized,
+{
+	let mut posts = sample_private_transfers(2, rng)
+		.into_iter()
+		.map(crate::TransferPost::from)
+		.collect::<Vec<_>>();
+	let second = posts.pop().unwrap();
+	let mut post = posts.pop().unwrap();
+	post.sender_posts.extend(second.sender_posts);
+	post
+}
+
+/// Tests that only valid custom transfer shapes are selected.
+#[test]
+fn transfer_shape_selection_should_work() {
+	assert_eq!(
+		TransferShapeKind::select(true, 1, 0, 1, 0),
+		Some(TransferShapeKind::Mint)
+	);
+	assert_eq!(
+		TransferShapeKind::select(false, 0, 2, 2, 0),
+		Some(TransferShapeKind::PrivateTransfer)
+	);
+	assert_eq!(
+		TransferShapeKind::select(true, 0, 2, 1, 1),
+		Some(TransferShapeKind::Reclaim)
+	);
+	assert_eq!(TransferShapeKind::select(false, 0, 4, 2, 0), Some(CONSOLIDATION));
+	assert!(CONSOLIDATION.is_valid_custom());
+	assert!(CONSOLIDATION.is_private());
+	assert_eq!(TransferShapeKind::select(true, 0, 4, 2, 0), None);
+	assert_eq!(TransferShapeKind::select(true, 1, 2, 2, 1), None);
+	assert_eq!(TransferShapeKind::select(true, 2, 0, 2, 0), None);
+	assert_eq!(TransferShapeKind::select(false, 0, 0, 0, 0), None);
+	assert_eq!(TransferShapeKind::select(false, 0, 256, 2, 0), None);
+	assert!(!TransferShapeKind::Custom {
+		sources: 0,
+		senders: 2,
+		receivers: 2,
+		sinks: 0
+	}
+	.is_valid_custom());
+}
+
+/// Tests that posts with a custom shape are only accepted once the shape is registered, and are
+/// then verified against the registered verifying context and charged the registered weight.
+#[test]
+fn register_transfer_shape_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let post = sample_consolidation(&mut rng);
+		assert_eq!(TransferShapeKind::of(&post), Some(CONSOLIDATION));
+		assert_noop!(
+			MantaPayPallet::private_transfer(Origin::signed(ALICE), post.clone()),
+			Error::<Test>::UnknownTransferShape,
+		);
+		let call = crate::Call::private_transfer_unsigned { post: post.clone() };
+		assert_eq!(
+			MantaPayPallet::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into()
+		);
+		let weight = 1_000_000_000;
+		let private_transfer = MantaPayPallet::verifying_context(TransferShapeKind::PrivateTransfer);
+		assert_ok!(MantaPayPallet::register_transfer_shape(
+			Origin::root(),
+			CONSOLIDATION,
+			VersionedParameters::new(1, private_transfer.into_owned()),
+			None,
+			weight,
+		));
+		assert_eq!(MantaPayPallet::transfer_weight(&post, 1), weight);
+		assert_eq!(MantaPayPallet::transfer_weight(&post, weight + 1), weight + 1);
+		assert_noop!(
+			MantaPayPallet::private_transfer(Origin::signed(ALICE), post.clone()),
+			Error::<Test>::InvalidProof,
+		);
+		assert_eq!(
+			MantaPayPallet::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadProof.into()
+		);
+		assert_ok!(MantaPayPallet::deregister_transfer_shape(
+			Origin::root(),
+			CONSOLIDATION
+		));
+		assert!(MantaPayPallet::verifying_context(CONSOLIDATION).is_empty());
+		assert_noop!(
+			MantaPayPallet::private_transfer(Origin::signed(ALICE), post),
+			Error::<Test>::UnknownTransferShape,
+		);
+	});
+}
+
+/// Tests that only valid custom shapes can be registered and only by the parameters origin.
+#[test]
+fn register_invalid_transfer_shape_should_not_work() {
+	new_test_ext().execute_with(|| {
+		let private_transfer =
+			MantaPayPallet::verifying_context(TransferShapeKind::PrivateTransfer).into_owned();
+		assert_noop!(
+			MantaPayPallet::register_transfer_shape(
+				Origin::signed(ALICE),
+				CONSOLIDATION,
+				VersionedParameters::new(1, private_transfer.clone()),
+				None,
+				0,
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			MantaPayPallet::register_transfer_shape(
+				Origin::root(),
+				TransferShapeKind::PrivateTransfer,
+				VersionedParameters::new(1, private_transfer.clone()),
+				None,
+				0,
+			),
+			Error::<Test>::InvalidShape,
+		);
+		assert_noop!(
+			MantaPayPallet::register_transfer_shape(
+				Origin::root(),
+				CONSOLIDATION,
+				VersionedParameters::new(1, vec![0; 8]),
+				None,
+				0,
+			),
+			Error::<Test>::InvalidParameters,
+		);
+		assert_noop!(
+			MantaPayPallet::register_transfer_shape(
+				Origin::root(),
+				CONSOLIDATION,
+				VersionedParameters::new(1, private_transfer.clone()),
+				Some(TransferPostLayout {
+					proof_length: 256,
+					ciphertext_length: 96,
+				}),
+				0,
+			),
+			Error::<Test>::UnsupportedTransferPostLayout,
+		);
+		assert_noop!(
+			MantaPayPallet::set_verifying_context(
+				Origin::root(),
+				CONSOLIDATION,
+				VersionedParameters::new(1, private_transfer),
+				None,
+			),
+			Error::<Test>::UnknownTransferShape,
+		);
+		assert_noop!(
+			MantaPayPallet::deregister_transfer_shape(Origin::root(), CONSOLIDATION),
+			Error::<Test>::UnknownTransferShape,
+		);
+	});
+}
+
+/// Tests that versioned posts are only accepted if their proof and ciphertexts have the lengths of
+/// the layout registered for their shape, and that only supported layouts can be registered.
+#[test]
+fn transfer_should_check_registered_layout() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + value + DEFAULT_ASSET_ED);
+		assert_eq!(
+			MantaPayPallet::supported_transfer_post_versions(),
+			vec![1, 2]
+		);
+		assert_eq!(
+			MantaPayPallet::supported_transfer_post_layouts(),
+			vec![TransferPostLayout::V1]
+		);
+		let post = TransferPostV2::from(sample_mint(value.with(asset_id), &mut rng));
+		assert_eq!(
+			MantaPayPallet::transfer_post_layout(TransferShapeKind::Mint),
+			TransferPostLayout::V1
+		);
+		assert!(post.has_layout(&TransferPostLayout::V1));
+		let mut padded = post.clone();
+		padded.validity_proof.resize(256, 0);
+		assert_noop!(
+			MantaPayPallet::transfer(Origin::signed(ALICE), padded.into()),
+			Error::<Test>::InvalidTransferPostLayout,
+		);
+		let mut truncated = post.clone();
+		truncated.receiver_posts[0].ciphertext.pop();
+		assert_noop!(
+			MantaPayPallet::transfer(Origin::signed(ALICE), truncated.into()),
+			Error::<Test>::InvalidTransferPostLayout,
+		);
+		let mint = MantaPayPallet::verifying_context(TransferShapeKind::Mint).into_owned();
+		assert_noop!(
+			MantaPayPallet::set_verifying_context(
+				Origin::root(),
+				TransferShapeKind::Mint,
+				VersionedParameters::new(1, mint.clone()),
+				Some(TransferPostLayout {
+					proof_length: 256,
+					..TransferPostLayout::V1
+				}),
+			),
+			Error::<Test>::UnsupportedTransferPostLayout,
+		);
+		assert_ok!(MantaPayPallet::set_verifying_context(
+			Origin::root(),
+			TransferShapeKind::Mint,
+			VersionedParameters::new(1, mint),
+			Some(TransferPostLayout::V1),
+		));
+		assert_ok!(MantaPayPallet::transfer(Origin::signed(ALICE), post.into()));
+		assert_ok!(MantaPayPallet::transfer(
+			Origin::signed(ALICE),
+			VersionedTransferPost::V1(sample_mint(value.with(asset_id), &mut rng).into())
+		));
+	});
+}
+
+/// Tests that the UTXO accumulator model can only be replaced while the ledger is empty.
+#[test]
+fn set_utxo_accumulator_model_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let model = MantaPayPallet::utxo_accumulator_model().into_owned();
+		assert_ok!(MantaPayPallet::set_utxo_accumulator_model(
+			Origin::root(),
+			VersionedParameters::new(1, model.clone()),
+		));
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		mint_tokens(asset_id, &[value], &mut rng);
+		assert_noop!(
+			MantaPayPallet::set_utxo_accumulator_model(
+				Origin::root(),
+				VersionedParameters::new(2, model),
+			),
+			Error::<Test>::LedgerNotEmpty,
+		);
+	});
+}
+
+/// Tests that Utxo accumulator outputs expire once their shard window is full and are pruned in
+/// `on_initialize`.
+#[test]
+fn utxo_accumulator_outputs_should_expire() {
+	new_test_ext().execute_with(|| {
+		let window = MantaPayUtxoAccumulatorOutputWindow::get();
+		let outputs = (0..=window as u8).map(|i| [i; 32]).collect::<Vec<_>>();
+		for output in &outputs {
+			MantaPayPallet::push_utxo_accumulator_output(0, *output);
+		}
+		assert!(!MantaPayPallet::has_utxo_accumulator_output(&outputs[0]));
+		for output in &outputs[1..] {
+			assert!(MantaPayPallet::has_utxo_accumulator_output(output));
+		}
+		assert!(crate::pallet::UtxoAccumulatorOutputs::<Test>::contains_key(&outputs[0]));
+		MantaPayPallet::on_initialize(1);
+		assert!(!crate::pallet::UtxoAccumulatorOutputs::<Test>::contains_key(&outputs[0]));
+		assert_eq!(
+			crate::pallet::ExpiredUtxoAccumulatorOutputs::<Test>::iter().count(),
+			0
+		);
+	});
+}
+
+/// Tests that the V0->V1 migration keeps every output valid until the shards are replayed over
+/// the next blocks, and then seeds the ring buffers with the most recent outputs of each shard and
+/// expires every older output.
+#[test]
+fn migrate_v0_to_v1_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		mint_tokens(asset_id, &value_distribution(8, value, &mut rng), &mut rng);
+		let (shard_index, _, (utxo, note)) = crate::pallet::Shards::<Test>::iter().next().unwrap();
+		let registration = (
+			crate::Wrap(crate::types::decode::<manta_pay::config::Utxo, 32>(utxo).unwrap()),
+			note.try_into().unwrap(),
+		);
+		for _ in 0..MantaPayUtxoAccumulatorOutputWindow::get() {
+			MantaPayPallet::ledger()
+				.register_all(vec![registration.clone()], &(crate::Wrap(()), ()));
+		}
+		let window = MantaPayUtxoAccumulatorOutputWindow::get();
+		let recent = crate::pallet::UtxoAccumulatorOutputRing::<Test>::iter_values()
+			.collect::<BTreeSet<_>>();
+		let outputs = crate::pallet::UtxoAccumulatorOutputs::<Test>::iter_keys().collect::<Vec<_>>();
+		assert!(recent.len() < outputs.len());
+		let head = crate::pallet::UtxoAccumulatorOutputRingHead::<Test>::get(shard_index);
+		let current = crate::pallet::UtxoAccumulatorOutputRing::<Test>::get(
+			shard_index,
+			(head + window - 1) % window,
+		);
+		let _ = crate::pallet::UtxoAccumulatorOutputRing::<Test>::remove_all(None);
+		let _ = crate::pallet::UtxoAccumulatorOutputRingHead::<Test>::remove_all(None);
+		let _ = crate::pallet::ExpiredUtxoAccumulatorOutputs::<Test>::remove_all(None);
+		crate::pallet::UtxoAccumulatorOutputRingWindow::<Test>::kill();
+		for output in &outputs {
+			frame_support::storage::unhashed::put(
+				&crate::pallet::UtxoAccumulatorOutputs::<Test>::hashed_key_for(output),
+				&(),
+			);
+		}
+		StorageVersion::new(0).put::<MantaPayPallet>();
+		assert_ok!(MantaPayPallet::pre_migrate_v0_to_v1());
+		MantaPayPallet::migrate_v0_to_v1();
+		assert_ok!(MantaPayPallet::post_migrate_v0_to_v1());
+		for output in &outputs {
+			assert!(MantaPayPallet::has_utxo_accumulator_output(output));
+		}
+		let mut blocks = 0;
+		while crate::pallet::ShardReplay::<Test>::exists() {
+			MantaPayPallet::on_initialize(1);
+			blocks += 1;
+		}
+		assert!(blocks > 1);
+		MantaPayPallet::on_initialize(1);
+		for output in &outputs {
+			assert_eq!(
+				MantaPayPallet::has_utxo_accumulator_output(output),
+				recent.contains(output)
+			);
+			assert_eq!(
+				crate::pallet::UtxoAccumulatorOutputs::<Test>::contains_key(output),
+				recent.contains(output)
+			);
+		}
+		let head = crate::pallet::UtxoAccumulatorOutputRingHead::<Test>::get(shard_index);
+		assert_eq!(
+			crate::pallet::UtxoAccumulatorOutputRing::<Test>::get(
+				shard_index,
+				(head + window - 1) % window
+			),
+			current
+		);
+	});
+}
+
+/// Tests that shrinking the Utxo accumulator output window keeps the most recent outputs of each
+/// shard and expires the others.
+#[test]
+fn utxo_accumulator_output_rings_should_resize() {
+	new_test_ext().execute_with(|| {
+		let window = MantaPayUtxoAccumulatorOutputWindow::get();
+		let outputs = (0..(window + window / 2) as u8)
+			.map(|i| [i; 32])
+			.collect::<Vec<_>>();
+		for (slot, output) in outputs.iter().enumerate() {
+			crate::pallet::UtxoAccumulatorOutputRing::<Test>::insert(0, slot as u32, output);
+			crate::pallet::UtxoAccumulatorOutputs::<Test>::insert(output, 0);
+		}
+		crate::pallet::UtxoAccumulatorOutputRingHead::<Test>::insert(0, outputs.len() as u32);
+		crate::pallet::UtxoAccumulatorOutputRingWindow::<Test>::put(2 * window);
+		MantaPayPallet::on_runtime_upgrade();
+		let expired = outputs.len() - window as usize;
+		for (i, output) in outputs.iter().enumerate() {
+			assert_eq!(MantaPayPallet::has_utxo_accumulator_output(output), i >= expired);
+		}
+		assert_eq!(
+			crate::pallet::UtxoAccumulatorOutputRing::<Test>::iter_prefix(0).count(),
+			window as usize
+		);
+		assert_eq!(
+			crate::pallet::UtxoAccumulatorOutputRingWindow::<Test>::get(),
+			Some(window)
+		);
+		MantaPayPallet::push_utxo_accumulator_output(0, [u8::MAX; 32]);
+		assert!(!MantaPayPallet::has_utxo_accumulator_output(&outputs[expired]));
+		assert!(MantaPayPallet::has_utxo_accumulator_output(&outputs[expired + 1]));
+	});
+}
+
+/// Tests that an unsigned [`PrivateTransfer`] is validated with its void numbers as tags and
+/// posted without an origin.
+#[test]
+fn private_transfer_unsigned_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let post: crate::TransferPost = sample_private_transfers(1, &mut rng).remove(0).into();
+		let call = crate::Call::private_transfer_unsigned { post: post.clone() };
+		let validity = MantaPayPallet::validate_unsigned(TransactionSource::External, &call)
+			.expect("The unsigned private transfer should be valid.");
+		assert_eq!(validity.priority, MantaPayUnsignedPriority::get());
+		assert_eq!(validity.longevity, MantaPayUnsignedLongevity::get());
+		assert_eq!(validity.provides.len(), post.sender_posts.len());
+		assert_ok!(MantaPayPallet::pre_dispatch(&call));
+		assert_ok!(MantaPayPallet::private_transfer_unsigned(
+			Origin::none(),
+			post.clone()
+		));
+		assert!(matches!(
+			System::events().last().unwrap().event,
+			Event::MantaPayPallet(crate::Event::PrivateTransfer { origin: None, .. })
+		));
+		assert_eq!(
+			MantaPayPallet::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
+
+/// Tests that an unsigned [`PrivateTransfer`] with an invalid proof is rejected by the pool.
+#[test]
+fn private_transfer_unsigned_with_invalid_proof_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let mut posts = sample_private_transfers(2, &mut rng)
+			.into_iter()
+			.map(crate::TransferPost::from)
+			.collect::<Vec<_>>();
+		let mut post = posts.remove(0);
+		post.validity_proof = posts[0].validity_proof;
+		assert_eq!(
+			MantaPayPallet::validate_unsigned(
+				TransactionSource::External,
+				&crate::Call::private_transfer_unsigned { post },
+			),
+			InvalidTransaction::BadProof.into()
+		);
+	});
+}
+
+/// Tests that only private transfers can be submitted unsigned.
+#[test]
+fn unsigned_mint_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		let call = crate::Call::private_transfer_unsigned {
+			post: sample_mint(value.with(asset_id), &mut rng).into(),
+		};
+		assert_eq!(
+			MantaPayPallet::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into()
+		);
+		assert_noop!(
+			MantaPayPallet::private_transfer_unsigned(
+				Origin::signed(ALICE),
+				sample_mint(value.with(asset_id), &mut rng).into()
+			),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+/// Tests that [`CheckVoidNumbers`] provides the same void number tags as unsigned private
+/// transfers and rejects spent void numbers.
+#[test]
+fn check_void_numbers_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let post: crate::TransferPost = sample_private_transfers(1, &mut rng).remove(0).into();
+		let call = crate::mock::Call::MantaPayPallet(crate::Call::private_transfer {
+			post: post.clone(),
+		});
+		let validity = CheckVoidNumbers::<Test>::new()
+			.validate(&ALICE, &call, &Default::default(), 0)
+			.expect("The private transfer should be valid.");
+		let unsigned_validity = MantaPayPallet::validate_unsigned(
+			TransactionSource::External,
+			&crate::Call::private_transfer_unsigned { post: post.clone() },
+		)
+		.expect("The unsigned private transfer should be valid.");
+		assert_eq!(validity.provides, unsigned_validity.provides);
+		assert_ok!(MantaPayPallet::private_transfer(Origin::signed(ALICE), post));
+		assert_eq!(
+			CheckVoidNumbers::<Test>::new().validate(&ALICE, &call, &Default::default(), 0),
+			InvalidTransaction::Stale.into()
+		);
+		assert_eq!(
+			CheckVoidNumbers::<Test>::new().pre_dispatch(&ALICE, &call, &Default::default(), 0),
+			Err(InvalidTransaction::Stale.into())
+		);
+		let call = crate::mock::Call::System(frame_system::Call::remark { remark: vec![] });
+		assert_eq!(
+			CheckVoidNumbers::<Test>::new().validate(&ALICE, &call, &Default::default(), 0),
+			Ok(Default::default())
+		);
+	});
+}
+
+/// Tests that a fee-sponsored [`Reclaim`] reimburses the relayer and hands the rest of the
+/// reclaimed value to the fee handler.
+#[test]
+fn to_public_with_fee_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		let post = sample_reclaim(asset_id, value, &mut rng);
+		assert_ok!(MantaPayPallet::to_public_with_fee(
+			Origin::signed(RELAYER),
+			post.into(),
+			10_000,
+		));
+		assert_eq!(Assets::balance(asset_id.0, RELAYER), 10_000);
+		assert_eq!(Assets::balance(asset_id.0, FEE_COLLECTOR), value.0 - 10_000);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::MantaPayPallet(crate::Event::ToPublicWithFee {
+				asset: crate::Asset::new(asset_id.0, value.0),
+				relayer: RELAYER,
+				relayer_fee: 10_000,
+			})
+		);
+	});
+}
+
+/// Tests that a relayer fee greater than the reclaimed value is rejected.
+#[test]
+fn to_public_with_excessive_relayer_fee_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		let post = sample_reclaim(asset_id, value, &mut rng);
+		assert_noop!(
+			MantaPayPallet::to_public_with_fee(Origin::signed(RELAYER), post.into(), value.0 + 1),
+			Error::<Test>::InvalidRelayerFee,
+		);
+	});
+}
+
+/// Tests that only [`Reclaim`] posts can pay fees.
+#[test]
+fn to_public_with_fee_from_mint_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		assert_noop!(
+			MantaPayPallet::to_public_with_fee(
+				Origin::signed(RELAYER),
+				sample_mint(value.with(asset_id), &mut rng).into(),
+				0,
+			),
+			Error::<Test>::InvalidShape,
+		);
+	});
+}
+
+/// Tests that posts are rejected before posting once their shard is full.
+#[test]
+fn to_private_into_full_shard_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		for shard_index in 0..=u8::MAX {
+			crate::pallet::ShardUtilization::<Test>::insert(
+				shard_index,
+				MantaPayPallet::SHARD_CAPACITY,
+			);
+		}
+		assert_noop!(
+			MantaPayPallet::to_private(
+				Origin::signed(ALICE),
+				sample_mint(value.with(asset_id), &mut rng).into()
+			),
+			Error::<Test>::ShardCapacityExceeded,
+		);
+	});
+}
+
+/// Tests that a post can fill a shard up to its capacity, and that the full shard then rejects
+/// further posts.
+#[test]
+fn to_private_filling_shard_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		let post = sample_mint(value.with(asset_id), &mut rng);
+		let shard_index = MerkleTreeConfiguration::tree_index(&post.receiver_posts[0].utxo);
+		crate::pallet::ShardUtilization::<Test>::insert(
+			shard_index,
+			MantaPayPallet::SHARD_CAPACITY - 1,
+		);
+		assert_ok!(MantaPayPallet::to_private(
+			Origin::signed(ALICE),
+			post.into()
+		));
+		assert_eq!(
+			crate::pallet::ShardUtilization::<Test>::get(shard_index),
+			MantaPayPallet::SHARD_CAPACITY
+		);
+		let (utxo, _) = crate::pallet::Shards::<Test>::get(shard_index, 0);
+		assert_noop!(
+			MantaPayPallet::check_shard_capacity([utxo].iter()),
+			Error::<Test>::ShardCapacityExceeded,
+		);
+	});
+}
+
+/// Tests that a post fails without registering anything if the UTXO accumulator model cannot be
+/// decoded.
+#[test]
+fn to_private_with_undecodable_utxo_accumulator_model_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		crate::pallet::UtxoAccumulatorModelParameters::<Test>::put(VersionedParameters::new(
+			1,
+			vec![],
+		));
+		assert_noop!(
+			MantaPayPallet::to_private(
+				Origin::signed(ALICE),
+				sample_mint(value.with(asset_id), &mut rng).into()
+			),
+			Error::<Test>::InternalLedgerError,
+		);
+	});
+}
+
+/// Tests that shard utilization is tracked and that a warning is emitted past the threshold.
+#[test]
+fn shard_utilization_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		mint_tokens(asset_id, &value_distribution(4, value, &mut rng), &mut rng);
+		for (shard_index, utilization) in crate::pallet::ShardUtilization::<Test>::iter() {
+			assert_eq!(
+				crate::pallet::Shards::<Test>::iter_prefix(shard_index).count() as u64,
+				utilization
+			);
+		}
+		let threshold =
+			MantaPayShardUtilizationThreshold::get().mul_ceil(MantaPayPallet::SHARD_CAPACITY);
+		for shard_index in 0..=u8::MAX {
+			crate::pallet::ShardUtilization::<Test>::insert(shard_index, threshold - 1);
+		}
+		mint_tokens(asset_id, &[AssetValue(0)], &mut rng);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			Event::MantaPayPallet(crate::Event::ShardUtilizationThresholdReached {
+				utilization,
+				..
+			}) if utilization == threshold
+		)));
+	});
+}
+
+/// Tests that the V1->V2 migration computes the utilization of every shard.
+#[test]
+fn migrate_v1_to_v2_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		mint_tokens(asset_id, &value_distribution(8, value, &mut rng), &mut rng);
+		let utilization = crate::pallet::ShardUtilization::<Test>::iter().collect::<Vec<_>>();
+		let _ = crate::pallet::ShardUtilization::<Test>::remove_all(None);
+		StorageVersion::new(1).put::<MantaPayPallet>();
+		assert_ok!(MantaPayPallet::pre_migrate_v1_to_v2());
+		MantaPayPallet::migrate_v1_to_v2();
+		assert_ok!(MantaPayPallet::post_migrate_v1_to_v2());
+		assert_eq!(
+			crate::pallet::ShardUtilization::<Test>::iter().collect::<Vec<_>>(),
+			utilization
+		);
+	});
+}
+
+/// Tests that the V2->V3 migration counts the void numbers and removes the legacy set size.
+#[test]
+fn migrate_v2_to_v3_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		private_transfer_test(2, None, &mut rng);
+		let void_numbers = crate::pallet::VoidNumberSetInsertionOrder::<Test>::iter()
+			.collect::<BTreeMap<_, _>>();
+		let size = void_numbers.len() as u64;
+		assert_eq!(
+			crate::pallet::VoidNumberSetInsertionOrder::<Test>::count() as u64,
+			size
+		);
+		let counter_key = frame_support::storage::storage_prefix(
+			b"MantaPayPallet",
+			b"CounterForVoidNumberSetInsertionOrder",
+		);
+		let counter = frame_support::storage::unhashed::get_raw(&counter_key).unwrap();
+		frame_support::storage::unhashed::kill(&counter_key);
+		frame_support::storage::migration::put_storage_value(
+			b"MantaPayPallet",
+			b"VoidNumberSetSize",
+			&[],
+			size,
+		);
+		StorageVersion::new(2).put::<MantaPayPallet>();
+		assert_ok!(MantaPayPallet::check_ledger_invariants());
+		assert_ok!(MantaPayPallet::pre_migrate_v2_to_v3());
+		MantaPayPallet::migrate_v2_to_v3();
+		assert_ok!(MantaPayPallet::post_migrate_v2_to_v3());
+		assert_eq!(
+			frame_support::storage::unhashed::get_raw(&counter_key),
+			Some(counter)
+		);
+		assert_eq!(
+			crate::pallet::VoidNumberSetInsertionOrder::<Test>::iter().collect::<BTreeMap<_, _>>(),
+			void_numbers
+		);
+		assert_ok!(MantaPayPallet::check_ledger_invariants());
+	});
+}
+
+/// Tests that the V3->V4 migration seeds the shielded deposits from the pallet account.
+#[test]
+fn migrate_v3_to_v4_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		initialize_test(asset_id, AssetValue(1_000_000) + DEFAULT_ASSET_ED);
+		mint_tokens(asset_id, &[AssetValue(400_000), AssetValue(600_000)], &mut rng);
+		let _ = crate::pallet::ShieldedDeposits::<Test>::remove_all(None);
+		StorageVersion::new(3).put::<MantaPayPallet>();
+		assert_ok!(MantaPayPallet::pre_migrate_v3_to_v4());
+		MantaPayPallet::migrate_v3_to_v4([asset_id.0, NATIVE_ASSET_ID.0]);
+		assert_ok!(MantaPayPallet::post_migrate_v3_to_v4());
+		assert_eq!(
+			crate::pallet::ShieldedDeposits::<Test>::get(asset_id.0),
+			Assets::balance(asset_id.0, MantaPayPallet::account_id())
+		);
+		assert!(!crate::pallet::ShieldedDeposits::<Test>::contains_key(
+			NATIVE_ASSET_ID.0
+		));
+		assert_ok!(MantaPayPallet::check_ledger_invariants());
+	});
+}
+
+/// Tests that a ledger registered at genesis matches the ledger it was exported from.
+#[test]
+fn genesis_ledger_should_match_exported_ledger() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	let (config, shard_trees, outputs) = new_test_ext().execute_with(|| {
+		private_transfer_test(2, None, &mut rng);
+		let mut utxos = crate::pallet::Shards::<Test>::iter().collect::<Vec<_>>();
+		utxos.sort_by_key(|(shard_index, leaf_index, _)| (*shard_index, *leaf_index));
+		let mut void_numbers =
+			crate::pallet::VoidNumberSetInsertionOrder::<Test>::iter().collect::<Vec<_>>();
+		void_numbers.sort_by_key(|(index, _)| *index);
+		let config = GenesisConfig {
+			utxos: utxos
+				.into_iter()
+				.map(|(shard_index, _, (utxo, note))| (shard_index, utxo, note))
+				.collect(),
+			void_numbers: void_numbers
+				.into_iter()
+				.map(|(_, void_number)| void_number)
+				.collect(),
+			balances: crate::pallet::ShieldedDeposits::<Test>::iter().collect(),
+			..Default::default()
+		};
+		(
+			config,
+			crate::pallet::ShardTrees::<Test>::iter().collect::<BTreeMap<_, _>>(),
+			crate::pallet::UtxoAccumulatorOutputs::<Test>::iter_keys().collect::<BTreeSet<_>>(),
+		)
+	});
+	assert!(!config.balances.is_empty());
+	let mut storage = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	pallet_assets::GenesisConfig::<Test> {
+		assets: config
+			.balances
+			.iter()
+			.map(|(asset_id, _)| (*asset_id, ALICE, true, DEFAULT_ASSET_ED))
+			.collect(),
+		metadata: vec![],
+		accounts: vec![],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	GenesisBuild::<Test>::assimilate_storage(&config, &mut storage).unwrap();
+	sp_io::TestExternalities::from(storage).execute_with(|| {
+		assert_eq!(
+			crate::pallet::ShardTrees::<Test>::iter().collect::<BTreeMap<_, _>>(),
+			shard_trees
+		);
+		for (asset_id, balance) in &config.balances {
+			assert_eq!(
+				crate::pallet::ShieldedDeposits::<Test>::get(asset_id),
+				*balance
+			);
+			assert_eq!(
+				Assets::balance(*asset_id, MantaPayPallet::account_id()),
+				*balance
+			);
+		}
+		let genesis_outputs =
+			crate::pallet::UtxoAccumulatorOutputs::<Test>::iter_keys().collect::<BTreeSet<_>>();
+		for (shard_index, _) in shard_trees {
+			let head = crate::pallet::UtxoAccumulatorOutputRingHead::<Test>::get(shard_index);
+			let slot = (head + MantaPayUtxoAccumulatorOutputWindow::get() - 1)
+				% MantaPayUtxoAccumulatorOutputWindow::get();
+			let output =
+				crate::pallet::UtxoAccumulatorOutputRing::<Test>::get(shard_index, slot).unwrap();
+			assert!(genesis_outputs.contains(&output));
+			assert!(outputs.contains(&output));
+		}
+		for (index, void_number) in config.void_numbers.iter().enumerate() {
+			assert_eq!(
+				crate::pallet::VoidNumberSetInsertionOrder::<Test>::get(index as u64),
+				*void_number
+			);
+		}
+		assert_ok!(MantaPayPallet::check_ledger_invariants());
+	});
+}
+
+/// Tests that the UTXO shards and the void number set can be rebuilt from events alone.
+#[test]
+fn ledger_should_be_rebuilt_from_events() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		reclaim_test(2, None, &mut rng);
+		private_transfer_test(2, None, &mut rng);
+		let mut shards = BTreeMap::new();
+		let mut void_numbers = BTreeSet::new();
+		for record in System::events() {
+			let (spent, registrations) = match record.event {
+				Event::MantaPayPallet(crate::Event::ToPrivate { registrations, .. }) => {
+					(vec![], registrations)
+				}
+				Event::MantaPayPallet(crate::Event::PrivateTransfer {
+					void_numbers,
+					registrations,
+					..
+				})
+				| Event::MantaPayPallet(crate::Event::ToPublic {
+					void_numbers,
+					registrations,
+					..
+				}) => (void_numbers, registrations),
+				_ => continue,
+			};
+			for void_number in spent {
+				assert!(void_numbers.insert(void_number));
+			}
+			for registration in registrations {
+				assert!(shards
+					.insert(
+						(registration.shard_index, registration.leaf_index),
+						(registration.utxo, registration.note),
+					)
+					.is_none());
+			}
+		}
+		assert_eq!(
+			crate::pallet::Shards::<Test>::iter()
+				.map(|(shard_index, leaf_index, entry)| ((shard_index, leaf_index), entry))
+				.collect::<BTreeMap<_, _>>(),
+			shards
+		);
+		assert_eq!(
+			crate::pallet::VoidNumberSet::<Test>::iter_keys().collect::<BTreeSet<_>>(),
+			void_numbers
+		);
+	});
+}
+
+/// Tests that assets without a shielding policy can be converted into private assets.
+#[test]
+fn to_private_without_shielding_policy_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		assert_ok!(MantaPayPallet::set_shielding_policy(
+			Origin::root(),
+			asset_id.0,
+			None
+		));
+		assert_ok!(MantaPayPallet::to_private(
+			Origin::signed(ALICE),
+			sample_mint(value.with(asset_id), &mut rng).into()
+		));
+	});
+}
+
+/// Tests that assets with a policy which is not shieldable cannot be converted into private assets.
+#[test]
+fn to_private_with_unshieldable_policy_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		assert_ok!(MantaPayPallet::set_shielding_policy(
+			Origin::root(),
+			asset_id.0,
+			Some(ShieldingPolicy::new(false, 0, u128::MAX))
+		));
+		assert_noop!(
+			MantaPayPallet::to_private(
+				Origin::signed(ALICE),
+				sample_mint(value.with(asset_id), &mut rng).into()
+			),
+			Error::<Test>::AssetNotShieldable,
+		);
+	});
+}
+
+/// Tests that mints below the minimum value of the shielding policy are rejected.
+#[test]
+fn to_private_below_minimum_value_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		assert_ok!(MantaPayPallet::set_shielding_policy(
+			Origin::root(),
+			asset_id.0,
+			Some(ShieldingPolicy::new(true, 1_000, u128::MAX))
+		));
+		assert_noop!(
+			MantaPayPallet::to_private(
+				Origin::signed(ALICE),
+				sample_mint(asset_id.value(999), &mut rng).into()
+			),
+			Error::<Test>::MintValueTooSmall,
+		);
+		assert_ok!(MantaPayPallet::to_private(
+			Origin::signed(ALICE),
+			sample_mint(asset_id.value(1_000), &mut rng).into()
+		));
+	});
+}
+
+/// Tests that mints which would exceed the shielded supply cap are rejected, and that public assets
+/// held by the pallet account which were not shielded do not count towards the cap.
+#[test]
+fn to_private_above_shielded_supply_cap_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		assert_ok!(MantaPayPallet::set_shielding_policy(
+			Origin::root(),
+			asset_id.0,
+			Some(ShieldingPolicy::new(true, 0, 600_000))
+		));
+		let reclaim = sample_reclaim(asset_id, AssetValue(1_000), &mut rng);
+		assert_ok!(MantaPayPallet::to_private(
+			Origin::signed(ALICE),
+			sample_mint(asset_id.value(599_000), &mut rng).into()
+		));
+		assert_ok!(FungibleLedger::<Test>::mint(
+			asset_id.0,
+			&MantaPayPallet::account_id(),
+			1_000_000
+		));
+		assert_noop!(
+			MantaPayPallet::to_private(
+				Origin::signed(ALICE),
+				sample_mint(asset_id.value(1), &mut rng).into()
+			),
+			Error::<Test>::ShieldedSupplyCapExceeded,
+		);
+		assert_ok!(MantaPayPallet::to_public(
+			Origin::signed(ALICE),
+			reclaim.into()
+		));
+		assert_ok!(MantaPayPallet::to_private(
+			Origin::signed(ALICE),
+			sample_mint(asset_id.value(1), &mut rng).into()
+		));
+	});
+}
+
+/// Tests that a mint which brings the shielded supply exactly to the cap is accepted, and that
+/// nothing more can be shielded afterwards.
+#[test]
+fn to_private_up_to_shielded_supply_cap_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		assert_ok!(MantaPayPallet::set_shielding_policy(
+			Origin::root(),
+			asset_id.0,
+			Some(ShieldingPolicy::new(true, 0, 600_000))
+		));
+		assert_ok!(MantaPayPallet::to_private(
+			Origin::signed(ALICE),
+			sample_mint(asset_id.value(599_000), &mut rng).into()
+		));
+		assert_noop!(
+			MantaPayPallet::to_private(
+				Origin::signed(ALICE),
+				sample_mint(asset_id.value(1_001), &mut rng).into()
+			),
+			Error::<Test>::ShieldedSupplyCapExceeded,
+		);
+		assert_ok!(MantaPayPallet::to_private(
+			Origin::signed(ALICE),
+			sample_mint(asset_id.value(1_000), &mut rng).into()
+		));
+		assert_eq!(
+			crate::pallet::ShieldedDeposits::<Test>::get(asset_id.0),
+			600_000
+		);
+		assert_noop!(
+			MantaPayPallet::to_private(
+				Origin::signed(ALICE),
+				sample_mint(asset_id.value(1), &mut rng).into()
+			),
+			Error::<Test>::ShieldedSupplyCapExceeded,
+		);
+	});
+}
+
+/// Tests that only the parameters origin can set shielding policies.
+#[test]
+fn set_shielding_policy_requires_parameters_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			MantaPayPallet::set_shielding_policy(
+				Origin::signed(ALICE),
+				8,
+				Some(ShieldingPolicy::unrestricted())
+			),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+/// Tests that a [`Reclaim`] can deposit the public assets into a third-party sink account.
+#[test]
+fn to_public_for_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		let post = sample_reclaim(asset_id, value, &mut rng);
+		let balance = Assets::balance(asset_id.0, ALICE);
+		assert_ok!(MantaPayPallet::to_public_for(
+			Origin::signed(RELAYER),
+			post.clone().into(),
+			BOB
+		));
+		assert_eq!(Assets::balance(asset_id.0, BOB), value.0);
+		assert_eq!(Assets::balance(asset_id.0, RELAYER), 0);
+		assert_eq!(Assets::balance(asset_id.0, ALICE), balance);
+		assert_noop!(
+			MantaPayPallet::to_public_for(Origin::signed(RELAYER), post.into(), BOB),
+			Error::<Test>::AssetSpent,
+		);
+	});
+}
+
+/// Tests that a [`Mint`] can withdraw the public assets from an approving source account.
+#[test]
+fn to_private_from_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		assert_ok!(MantaPayPallet::approve_to_private(
+			Origin::signed(ALICE),
+			RELAYER,
+			asset_id.0,
+			600_000
+		));
+		assert_ok!(MantaPayPallet::to_private_from(
+			Origin::signed(RELAYER),
+			ALICE,
+			sample_mint(asset_id.value(400_000), &mut rng).into()
+		));
+		assert_eq!(
+			Assets::balance(asset_id.0, ALICE),
+			value.0 + DEFAULT_ASSET_ED - 400_000
+		);
+		assert_eq!(
+			crate::pallet::ToPrivateApprovals::<Test>::get(&ALICE, (RELAYER, asset_id.0)),
+			Some(200_000)
+		);
+		assert_noop!(
+			MantaPayPallet::to_private_from(
+				Origin::signed(RELAYER),
+				ALICE,
+				sample_mint(asset_id.value(200_001), &mut rng).into()
+			),
+			Error::<Test>::InsufficientApproval,
+		);
+		assert_ok!(MantaPayPallet::to_private_from(
+			Origin::signed(RELAYER),
+			ALICE,
+			sample_mint(asset_id.value(200_000), &mut rng).into()
+		));
+		assert_eq!(
+			crate::pallet::ToPrivateApprovals::<Test>::get(&ALICE, (RELAYER, asset_id.0)),
+			None
+		);
+	});
+}
+
+/// Tests that a [`Mint`] cannot withdraw from a source account without its approval.
+#[test]
+fn to_private_from_without_approval_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		assert_ok!(MantaPayPallet::approve_to_private(
+			Origin::signed(ALICE),
+			BOB,
+			asset_id.0,
+			value.0
+		));
+		assert_noop!(
+			MantaPayPallet::to_private_from(
+				Origin::signed(RELAYER),
+				ALICE,
+				sample_mint(value.with(asset_id), &mut rng).into()
+			),
+			Error::<Test>::InsufficientApproval,
+		);
+	});
+}
+
+/// Tests that a [`Mint`] can shield assets deposited into the pallet account.
+#[test]
+fn to_private_from_deposit_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		assert_ok!(FungibleLedger::<Test>::transfer(
+			asset_id.0,
+			&ALICE,
+			&MantaPayPallet::account_id(),
+			value.0
+		));
+		let balance = Assets::balance(asset_id.0, MantaPayPallet::account_id());
+		assert_ok!(MantaPayPallet::to_private_from_deposit(
+			sample_mint(value.with(asset_id), &mut rng).into(),
+			crate::Asset::new(asset_id.0, value.0)
+		));
+		assert_eq!(
+			Assets::balance(asset_id.0, MantaPayPallet::account_id()),
+			balance
+		);
+		assert_eq!(
+			crate::pallet::ShieldedDeposits::<Test>::get(asset_id.0),
+			value.0
+		);
+	});
+}
+
+/// Tests that a [`Mint`] cannot shield a deposit of a different asset.
+#[test]
+fn to_private_from_invalid_deposit_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		let post = sample_mint(value.with(asset_id), &mut rng);
+		for asset in [
+			crate::Asset::new(asset_id.0, value.0 - 1),
+			crate::Asset::new(NATIVE_ASSET_ID.0, value.0),
+		] {
+			assert_noop!(
+				MantaPayPallet::to_private_from_deposit(post.clone().into(), asset),
+				Error::<Test>::InvalidDeposit,
+			);
+		}
+		let reclaim = sample_reclaim(asset_id, value, &mut rng);
+		assert_noop!(
+			MantaPayPallet::to_private_from_deposit(
+				reclaim.into(),
+				crate::Asset::new(asset_id.0, value.0)
+			),
+			Error::<Test>::InvalidDeposit,
+		);
+	});
+}
+
+/// Tests that a [`Reclaim`] can send the public assets straight to another chain.
+#[test]
+fn to_public_remote_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		let post = sample_reclaim(asset_id, value, &mut rng);
+		let balance = Assets::balance(asset_id.0, MantaPayPallet::account_id());
+		assert_ok!(MantaPayPallet::to_public_remote(
+			Origin::signed(RELAYER),
+			post.into(),
+			BOB,
+			0
+		));
+		assert_eq!(Assets::balance(asset_id.0, BOB), value.0);
+		assert_eq!(Assets::balance(asset_id.0, RELAYER), 0);
+		assert_eq!(
+			Assets::balance(asset_id.0, MantaPayPallet::account_id()),
+			balance - value.0
+		);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::MantaPayPallet(crate::Event::ToPublicRemote {
+				asset: crate::Asset::new(asset_id.0, value.0),
+				destination: BOB,
+			})
+		);
+	});
+}
+
+/// Tests that a [`Reclaim`] is rolled back if sending the public assets to another chain fails.
+#[test]
+fn to_public_remote_to_unreachable_destination_should_not_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		let post = sample_reclaim(asset_id, value, &mut rng);
+		assert_noop!(
+			MantaPayPallet::to_public_remote(
+				Origin::signed(RELAYER),
+				post.clone().into(),
+				UNREACHABLE,
+				0
+			),
+			DispatchError::Other("Unreachable destination."),
+		);
+		assert_ok!(MantaPayPallet::to_public_remote(
+			Origin::signed(RELAYER),
+			post.into(),
+			BOB,
+			0
+		));
+	});
+}
+
+/// Tests that the ledger invariants hold after posting and catch inconsistent storage.
+#[test]
+fn ledger_invariants_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		assert_ok!(MantaPayPallet::check_ledger_invariants());
+		reclaim_test(2, None, &mut rng);
+		private_transfer_test(2, None, &mut rng);
+		assert_ok!(MantaPayPallet::check_ledger_invariants());
+		let (shard_index, leaf_index, entry) = crate::pallet::Shards::<Test>::iter().next().unwrap();
+		crate::pallet::UtxoSet::<Test>::remove(entry.0);
+		assert!(MantaPayPallet::check_ledger_invariants().is_err());
+		crate::pallet::UtxoSet::<Test>::insert(entry.0, ());
+		crate::pallet::Shards::<Test>::remove(shard_index, leaf_index);
+		crate::pallet::UtxoSet::<Test>::remove(entry.0);
+		assert!(MantaPayPallet::check_ledger_invariants().is_err());
+		crate::pallet::Shards::<Test>::insert(shard_index, leaf_index, entry.clone());
+		crate::pallet::UtxoSet::<Test>::insert(entry.0, ());
+		assert_ok!(MantaPayPallet::check_ledger_invariants());
+		crate::pallet::VoidNumberSet::<Test>::insert([7; 32], ());
+		assert!(MantaPayPallet::check_ledger_invariants().is_err());
+	});
+}
+
+/// Tests that the shielded deposits follow the public assets moved by posts.
+#[test]
+fn shielded_deposits_should_work() {
+	require_proving_context!();
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		let post = sample_reclaim(asset_id, AssetValue(400_000), &mut rng);
+		mint_tokens(asset_id, &[AssetValue(100_000)], &mut rng);
+		assert_eq!(
+			crate::pallet::ShieldedDeposits::<Test>::get(asset_id.0),
+			500_000
+		);
+		assert_ok!(MantaPayPallet::to_public(Origin::signed(ALICE), post.into()));
+		assert_eq!(
+			crate::pallet::ShieldedDeposits::<Test>::get(asset_id.0),
+			100_000
+		);
+		assert_ok!(MantaPayPallet::check_ledger_invariants());
+		crate::pallet::ShieldedDeposits::<Test>::insert(asset_id.0, value.0);
+		assert!(MantaPayPallet::check_ledger_invariants().is_err());
+	});
+}
+
+/// Tests that posts refund the shard storage accesses saved by registering UTXOs into the same
+/// shard.
+#[test]
+fn actual_post_weight_should_refund_shared_shards() {
+	use crate::{types::UtxoRegistration, WeightInfo};
+	let registration = |shard_index, leaf_index| UtxoRegistration {
+		utxo: [shard_index; 32],
+		note: Default::default(),
+		shard_index,
+		leaf_index,
+	};
+	let weight = <Test as crate::Config>::WeightInfo::private_transfer();
+	assert_eq!(
+		MantaPayPallet::actual_post_weight(weight, &[registration(0, 0), registration(1, 0)]),
+		weight
+	);
+	assert_eq!(
+		MantaPayPallet::actual_post_weight(weight, &[registration(0, 0), registration(0, 1)]),
+		weight
+			- (<Test as crate::Config>::WeightInfo::register_utxos(2)
+				- <Test as crate::Config>::WeightInfo::register_utxos(1))
+	);
+	assert_eq!(MantaPayPallet::actual_post_weight(weight, &[]), weight);
+}
+
+/// Tests that posting returns its actual weight.
+#[test]
+fn to_private_should_return_actual_weight() {
+	require_proving_context!();
+	use crate::WeightInfo;
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let asset_id = rng.gen();
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + DEFAULT_ASSET_ED);
+		let post_info = MantaPayPallet::to_private(
+			Origin::signed(ALICE),
+			sample_mint(value.with(asset_id), &mut rng).into(),
+		)
+		.unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(<Test as crate::Config>::WeightInfo::to_private())
+		);
+	});
+}
+
+/// Tests that frozen mints and reclaims are rejected for the frozen asset only.
+#[test]
+fn frozen_mint_and_reclaim_should_not_work() {
+	require_proving_context!();
+	use crate::types::LedgerOperation;
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let asset_id = rng.gen();
+		let other_asset_id = AssetId(asset_id.0.wrapping_add(1));
+		let value = AssetValue(1_000_000);
+		initialize_test(asset_id, value + value + DEFAULT_ASSET_ED);
+		initialize_test(other_asset_id, value + DEFAULT_ASSET_ED);
+		let reclaim = sample_reclaim(asset_id, value, &mut rng);
+		assert_ok!(MantaPayPallet::set_frozen(
+			Origin::root(),
+			LedgerOperation::Mint,
+			Some(asset_id.0),
+			true
+		));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::MantaPayPallet(crate::Event::OperationFrozen {
+				operation: LedgerOperation::Mint,
+				asset_id: Some(asset_id.0),
+			})
+		);
+		assert_noop!(
+			MantaPayPallet::to_private(
+				Origin::signed(ALICE),
+				sample_mint(value.with(asset_id), &mut rng).into()
+			),
+			Error::<Test>::MintFrozen
+		);
+		mint_tokens(other_asset_id, &[value], &mut rng);
+		assert_ok!(MantaPayPallet::set_frozen(
+			Origin::root(),
+			LedgerOperation::Reclaim,
+			None,
+			true
+		));
+		assert_noop!(
+			MantaPayPallet::to_public(Origin::signed(ALICE), reclaim.clone().into()),
+			Error::<Test>::ReclaimFrozen
+		);
+		assert_ok!(MantaPayPallet::set_frozen(
+			Origin::root(),
+			LedgerOperation::Reclaim,
+			None,
+			false
+		));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::MantaPayPallet(crate::Event::OperationUnfrozen {
+				operation: LedgerOperation::Reclaim,
+				asset_id: None,
+			})
+		);
+		assert_ok!(MantaPayPallet::to_public(
+			Origin::signed(ALICE),
+			reclaim.into()
+		));
+	});
+}
+
+/// Tests that frozen private transfers are rejected, and can only be frozen for all assets.
+#[test]
+fn frozen_private_transfer_should_not_work() {
+	require_proving_context!();
+	use crate::types::LedgerOperation;
+	let mut rng = thread_rng();
+	new_test_ext().execute_with(|| {
+		let post: crate::TransferPost = sample_private_transfers(1, &mut rng).remove(0).into();
+		assert_noop!(
+			MantaPayPallet::set_frozen(
+				Origin::signed(ALICE),
+				LedgerOperation::PrivateTransfer,
+				None,
+				true
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			MantaPayPallet::set_frozen(
+				Origin::root(),
+				LedgerOperation::PrivateTransfer,
+				Some(8),
+				true
+			),
+			Error::<Test>::InvalidFreeze
+		);
+		assert_ok!(MantaPayPallet::set_frozen(
+			Origin::root(),
+			LedgerOperation::PrivateTransfer,
+			None,
+			true
+		));
+		assert_noop!(
+			MantaPayPallet::private_transfer(Origin::signed(ALICE), post.clone()),
+			Error::<Test>::PrivateTransferFrozen
+		);
+		assert_ok!(MantaPayPallet::set_frozen(
+			Origin::root(),
+			LedgerOperation::PrivateTransfer,
+			None,
+			false
+		));
+		assert_ok!(MantaPayPallet::private_transfer(
+			Origin::signed(ALICE),
+			post
+		));
+	});
+}
@@ -0,0 +1,381 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of pallet-manta-pay.
+//
+// pallet-manta-pay is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// pallet-manta-pay is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with pallet-manta-pay.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Wallet Simulation
+//!
+//! Drives many simulated wallets against the mock runtime with random mints, private transfers
+//! and reclaims across several assets. Wallets sync their UTXO accumulator from pallet storage,
+//! and balance conservation is checked against the [`FungibleLedger`] after every step, so any
+//! ledger bug shows up as a failing seed which can be replayed deterministically.
+
+use crate::{
+	mock::{new_test_ext, MantaAssetRegistrar, MantaPayPallet, Origin, Test},
+	test::payment::{
+		proving_context, require_proving_context, UtxoAccumulator, PARAMETERS,
+		UTXO_ACCUMULATOR_MODEL,
+	},
+	types::{decode, ShieldingPolicy, SHARD_COUNT},
+	FungibleLedger,
+};
+use frame_support::{assert_ok, dispatch::DispatchResultWithPostInfo};
+use manta_accounting::{
+	asset::{Asset, AssetId, AssetValue},
+	transfer::{self, PreSender, SpendingKey},
+};
+use manta_crypto::{
+	accumulator::Accumulator,
+	rand::{Rand, RngCore, Sample},
+};
+use manta_pay::config::{self, FullParameters, PrivateTransfer, Reclaim};
+use manta_primitives::{
+	assets::{AssetRegistrar, AssetRegistrarMetadata, FungibleLedger as _},
+	constants::DEFAULT_ASSET_ED,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use sp_runtime::AccountId32;
+
+/// Seeds of the Simulations Run by the Tests
+///
+/// Add any seed which exposes a ledger bug to this list once it is fixed.
+const SEEDS: [u64; 2] = [0, 1];
+
+/// Number of Steps in each Simulation
+const STEPS: usize = 12;
+
+/// Number of Simulated Wallets
+const WALLETS: usize = 3;
+
+/// Asset Ids used in the Simulation
+const ASSET_IDS: [u32; 2] = [8, 9];
+
+/// Initial Public Balance of each Wallet in each Asset
+const INITIAL_BALANCE: u128 = 1_000_000;
+
+/// Simulated Wallet
+struct Wallet {
+	/// Public Account
+	account: AccountId32,
+
+	/// Spending Key
+	spending_key: SpendingKey<config::Config>,
+
+	/// Unspent Private Assets
+	assets: Vec<(Asset, PreSender<config::Config>)>,
+}
+
+impl Wallet {
+	/// Returns the private balance of the wallet in the asset with the given `id`.
+	#[inline]
+	fn private_balance(&self, id: AssetId) -> u128 {
+		self.assets
+			.iter()
+			.filter(|(asset, _)| asset.id == id)
+			.map(|(asset, _)| asset.value.0)
+			.sum()
+	}
+
+	/// Takes two unspent private assets with the given `id` out of the wallet, if there are two.
+	#[inline]
+	fn take_pair(&mut self, id: AssetId) -> Option<[(Asset, PreSender<config::Config>); 2]> {
+		let mut indices = self
+			.assets
+			.iter()
+			.enumerate()
+			.filter(|(_, (asset, _))| asset.id == id)
+			.map(|(index, _)| index);
+		let first = indices.next()?;
+		let second = indices.next()?;
+		let second = self.assets.remove(second);
+		let first = self.assets.remove(first);
+		Some([first, second])
+	}
+}
+
+/// Wallet Simulation
+struct Simulation {
+	/// Simulation Seed
+	seed: u64,
+
+	/// Simulated Wallets
+	wallets: Vec<Wallet>,
+
+	/// UTXO Accumulator Synced from Pallet Storage
+	utxo_accumulator: UtxoAccumulator,
+
+	/// Number of UTXOs Synced from each Shard
+	synced: [u64; SHARD_COUNT],
+
+	/// Simulation RNG
+	rng: StdRng,
+}
+
+impl Simulation {
+	/// Builds a new simulation from `seed`, registering the simulated assets and funding the
+	/// public account of every wallet.
+	#[inline]
+	fn new(seed: u64) -> Self {
+		let mut rng = StdRng::seed_from_u64(seed);
+		for id in ASSET_IDS {
+			assert_ok!(MantaAssetRegistrar::create_asset(
+				id,
+				DEFAULT_ASSET_ED,
+				AssetRegistrarMetadata::default().into(),
+				true
+			));
+			assert_ok!(FungibleLedger::<Test>::mint(
+				id,
+				&MantaPayPallet::account_id(),
+				DEFAULT_ASSET_ED
+			));
+			assert_ok!(MantaPayPallet::set_shielding_policy(
+				Origin::root(),
+				id,
+				Some(ShieldingPolicy::unrestricted())
+			));
+		}
+		let wallets = (0..WALLETS)
+			.map(|index| {
+				let account = AccountId32::new([0x10 + index as u8; 32]);
+				for id in ASSET_IDS {
+					assert_ok!(FungibleLedger::<Test>::mint(id, &account, INITIAL_BALANCE));
+				}
+				Wallet {
+					account,
+					spending_key: SpendingKey::gen(&mut rng),
+					assets: Vec::new(),
+				}
+			})
+			.collect();
+		Self {
+			seed,
+			wallets,
+			utxo_accumulator: UtxoAccumulator::new(UTXO_ACCUMULATOR_MODEL.clone()),
+			synced: [0; SHARD_COUNT],
+			rng,
+		}
+	}
+
+	/// Samples an index below `bound`.
+	#[inline]
+	fn sample_index(&mut self, bound: usize) -> usize {
+		(self.rng.next_u64() % bound as u64) as usize
+	}
+
+	/// Samples a value between `0` and `max` inclusive.
+	#[inline]
+	fn sample_value(&mut self, max: u128) -> AssetValue {
+		let value = ((self.rng.next_u64() as u128) << 64) | self.rng.next_u64() as u128;
+		AssetValue(value % (max + 1))
+	}
+
+	/// Asserts that `result` is `Ok`, reporting the simulation seed and `step` otherwise.
+	#[inline]
+	fn assert_step(&self, step: usize, result: DispatchResultWithPostInfo) {
+		if let Err(err) = result {
+			panic!(
+				"Simulation with seed {} failed at step {}: {:?}",
+				self.seed, step, err
+			);
+		}
+	}
+
+	/// Syncs the UTXO accumulator with the shards in pallet storage, in leaf order.
+	#[inline]
+	fn sync(&mut self) {
+		for shard_index in 0..SHARD_COUNT {
+			while let Ok((utxo, _)) =
+				crate::pallet::Shards::<Test>::try_get(shard_index as u8, self.synced[shard_index])
+			{
+				let utxo = decode::<config::Utxo, 32>(utxo).expect("Unable to decode the UTXO.");
+				assert!(
+					self.utxo_accumulator.insert(&utxo),
+					"Simulation with seed {}: unable to insert UTXO into shard {}.",
+					self.seed,
+					shard_index,
+				);
+				self.synced[shard_index] += 1;
+			}
+		}
+	}
+
+	/// Mints a random amount of a random asset from the public balance of a random wallet.
+	#[inline]
+	fn mint(&mut self, step: usize) {
+		let wallet = self.sample_index(WALLETS);
+		let id = AssetId(ASSET_IDS[self.sample_index(ASSET_IDS.len())]);
+		let balance = FungibleLedger::<Test>::balance(id.0, &self.wallets[wallet].account)
+			.expect("Simulated assets are registered.");
+		let asset = self
+			.sample_value(balance.saturating_sub(DEFAULT_ASSET_ED))
+			.with(id);
+		let (post, pre_sender) = transfer::test::sample_mint(
+			&proving_context().mint,
+			FullParameters::new(&PARAMETERS, self.utxo_accumulator.model()),
+			&self.wallets[wallet].spending_key,
+			asset,
+			&mut self.rng,
+		)
+		.expect("Unable to build MINT proof.");
+		let origin = Origin::signed(self.wallets[wallet].account.clone());
+		self.assert_step(step, MantaPayPallet::to_private(origin, post.into()));
+		self.wallets[wallet].assets.push((asset, pre_sender));
+	}
+
+	/// Spends two private assets of a random wallet, sending a random amount to a random wallet,
+	/// either privately or, if `reclaim` is `true`, to the public account of the spending wallet.
+	///
+	/// Mints a zero-valued asset instead if the wallet does not hold two assets of the same kind.
+	#[inline]
+	fn spend(&mut self, step: usize, reclaim: bool) {
+		let wallet = self.sample_index(WALLETS);
+		let id = AssetId(ASSET_IDS[self.sample_index(ASSET_IDS.len())]);
+		let inputs = match self.wallets[wallet].take_pair(id) {
+			Some(inputs) => inputs,
+			_ => {
+				let (post, pre_sender) = transfer::test::sample_mint(
+					&proving_context().mint,
+					FullParameters::new(&PARAMETERS, self.utxo_accumulator.model()),
+					&self.wallets[wallet].spending_key,
+					id.value(0),
+					&mut self.rng,
+				)
+				.expect("Unable to build MINT proof.");
+				let origin = Origin::signed(self.wallets[wallet].account.clone());
+				self.assert_step(step, MantaPayPallet::to_private(origin, post.into()));
+				self.wallets[wallet].assets.push((id.value(0), pre_sender));
+				return;
+			}
+		};
+		let total = inputs[0].0.value.0 + inputs[1].0.value.0;
+		let value = self.sample_value(total);
+		let change = id.value(total - value.0);
+		let [(_, pre_sender_0), (_, pre_sender_1)] = inputs;
+		let senders = [pre_sender_0, pre_sender_1].map(|pre_sender| {
+			pre_sender.upgrade(&self.utxo_accumulator).unwrap_or_else(|| {
+				panic!(
+					"Simulation with seed {}: wallet UTXO is missing from the synced ledger.",
+					self.seed
+				)
+			})
+		});
+		let (change_receiver, change_pre_sender) = self.wallets[wallet]
+			.spending_key
+			.internal_pair(&PARAMETERS, self.rng.gen(), change);
+		let origin = Origin::signed(self.wallets[wallet].account.clone());
+		if reclaim {
+			let post = Reclaim::build(senders, [change_receiver], value.with(id))
+				.into_post(
+					FullParameters::new(&PARAMETERS, self.utxo_accumulator.model()),
+					&proving_context().reclaim,
+					&mut self.rng,
+				)
+				.expect("Unable to build RECLAIM proof.");
+			self.assert_step(step, MantaPayPallet::to_public(origin, post.into()));
+		} else {
+			let receiver = self.sample_index(WALLETS);
+			let (value_receiver, value_pre_sender) = self.wallets[receiver]
+				.spending_key
+				.internal_pair(&PARAMETERS, self.rng.gen(), value.with(id));
+			let post = PrivateTransfer::build(senders, [value_receiver, change_receiver])
+				.into_post(
+					FullParameters::new(&PARAMETERS, self.utxo_accumulator.model()),
+					&proving_context().private_transfer,
+					&mut self.rng,
+				)
+				.expect("Unable to build PRIVATE_TRANSFER proof.");
+			self.assert_step(step, MantaPayPallet::private_transfer(origin, post.into()));
+			self.wallets[receiver]
+				.assets
+				.push((value.with(id), value_pre_sender));
+		}
+		self.wallets[wallet]
+			.assets
+			.push((change, change_pre_sender));
+	}
+
+	/// Checks that the public and private balances of every asset are conserved.
+	#[inline]
+	fn check_conservation(&self, step: usize) {
+		for id in ASSET_IDS {
+			let public = self
+				.wallets
+				.iter()
+				.map(|wallet| FungibleLedger::<Test>::balance(id, &wallet.account).unwrap())
+				.sum::<u128>();
+			let shielded = FungibleLedger::<Test>::balance(id, &MantaPayPallet::account_id())
+				.unwrap()
+				- DEFAULT_ASSET_ED;
+			let private = self
+				.wallets
+				.iter()
+				.map(|wallet| wallet.private_balance(AssetId(id)))
+				.sum::<u128>();
+			assert_eq!(
+				public + shielded,
+				WALLETS as u128 * INITIAL_BALANCE,
+				"Simulation with seed {}: supply of asset {} changed at step {}.",
+				self.seed,
+				id,
+				step,
+			);
+			assert_eq!(
+				shielded,
+				private,
+				"Simulation with seed {}: shielded balance of asset {} does not match the wallets \
+				 at step {}.",
+				self.seed,
+				id,
+				step,
+			);
+		}
+		if let Err(err) = MantaPayPallet::check_ledger_invariants() {
+			panic!(
+				"Simulation with seed {}: ledger invariant broken at step {}: {}",
+				self.seed, step, err
+			);
+		}
+	}
+
+	/// Runs `steps`-many random steps, syncing the wallets and checking conservation after each.
+	#[inline]
+	fn run(&mut self, steps: usize) {
+		for step in 0..steps {
+			match self.sample_index(3) {
+				0 => self.mint(step),
+				1 => self.spend(step, false),
+				_ => self.spend(step, true),
+			}
+			self.sync();
+			self.check_conservation(step);
+		}
+	}
+}
+
+/// Runs the simulation with the given `seed`.
+#[inline]
+fn simulate(seed: u64) {
+	new_test_ext().execute_with(|| Simulation::new(seed).run(STEPS));
+}
+
+/// Tests that random mints, private transfers and reclaims across many wallets and assets
+/// conserve balances and keep the ledger consistent.
+#[test]
+fn simulation_should_conserve_balances() {
+	require_proving_context!();
+	for seed in SEEDS {
+		simulate(seed);
+	}
+}
@@ -19,6 +19,14 @@
 use super::*;
 use manta_util::into_array_unchecked;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Number of UTXO Shards
+///
+/// Shards are indexed by a `u8` so there are exactly `256` of them.
+pub const SHARD_COUNT: usize = u8::MAX as usize + 1;
+
 /// Encodes the SCALE encodable `value` into a byte array with the given length `N`.
 #[inline]
 pub(crate) fn encode<T, const N: usize>(value: T) -> [u8; N]
@@ -38,6 +46,52 @@ where
 	T::decode(&mut bytes.as_slice()).map_err(|_| ())
 }
 
+/// Decodes all of `bytes` into the SCALE decodable type `T` returning a blanket error if decoding
+/// fails or leaves some of `bytes` unread.
+#[inline]
+pub(crate) fn decode_exact<T>(mut bytes: &[u8]) -> Result<T, ()>
+where
+	T: Decode,
+{
+	let value = T::decode(&mut bytes).map_err(|_| ())?;
+	bytes.is_empty().then(|| value).ok_or(())
+}
+
+/// Serializes fixed-size byte arrays which are too large for the default `serde` implementations.
+#[cfg(feature = "serde")]
+pub(crate) mod byte_array {
+	use super::*;
+	use serde::{de::Error, Deserializer, Serializer};
+
+	/// Serializes `bytes` as a sequence of bytes.
+	#[inline]
+	pub fn serialize<S, const N: usize>(bytes: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		bytes.as_slice().serialize(serializer)
+	}
+
+	/// Deserializes a sequence of exactly `N` bytes.
+	#[inline]
+	pub fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let bytes = Vec::<u8>::deserialize(deserializer)?;
+		let len = bytes.len();
+		bytes
+			.try_into()
+			.map_err(|_| D::Error::invalid_length(len, &"a byte array of the expected length"))
+	}
+}
+
+/// Unspent Transaction Output Type
+pub type Utxo = [u8; 32];
+
+/// Void Number Type
+pub type VoidNumber = [u8; 32];
+
 /// Asset
 #[derive(
 	Clone,
@@ -71,12 +125,14 @@ impl Asset {
 }
 
 /// Encrypted Note
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[derive(Clone, Debug, Decode, Encode, Eq, Hash, MaxEncodedLen, PartialEq, TypeInfo)]
 pub struct EncryptedNote {
 	/// Ephemeral Public Key
 	pub ephemeral_public_key: [u8; 32],
 
 	/// Ciphertext
+	#[cfg_attr(feature = "serde", serde(with = "byte_array"))]
 	pub ciphertext: [u8; 68],
 }
 
@@ -112,6 +168,25 @@ impl TryFrom<EncryptedNote> for config::EncryptedNote {
 	}
 }
 
+/// UTXO Registration
+///
+/// Records the position of a UTXO inserted into the ledger, as well as its encrypted note, so that
+/// the contents of the UTXO shards can be rebuilt from events alone.
+#[derive(Clone, Debug, Decode, Encode, Eq, Hash, MaxEncodedLen, PartialEq, TypeInfo)]
+pub struct UtxoRegistration {
+	/// Registered UTXO
+	pub utxo: Utxo,
+
+	/// Encrypted Note
+	pub note: EncryptedNote,
+
+	/// Shard Index
+	pub shard_index: u8,
+
+	/// Leaf Index in the Shard
+	pub leaf_index: u64,
+}
+
 /// Sender Post
 #[derive(Clone, Debug, Decode, Encode, Eq, Hash, MaxEncodedLen, PartialEq, TypeInfo)]
 pub struct SenderPost {
@@ -236,6 +311,543 @@ impl TryFrom<TransferPost> for config::TransferPost {
 	}
 }
 
+/// Transfer Post Layout
+///
+/// Lengths of the serialized proofs and ciphertexts of the [`TransferPostV2`] posts verified by a
+/// verifying context.
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(Clone, Copy, Debug, Decode, Encode, Eq, Hash, MaxEncodedLen, PartialEq, TypeInfo)]
+pub struct TransferPostLayout {
+	/// Validity Proof Length
+	pub proof_length: u32,
+
+	/// Ciphertext Length
+	pub ciphertext_length: u32,
+}
+
+impl TransferPostLayout {
+	/// Layout of the [`TransferPost`] posts
+	pub const V1: Self = Self {
+		proof_length: 192,
+		ciphertext_length: 68,
+	};
+
+	/// Layouts which the proof system of the pallet can verify
+	///
+	/// Only these layouts can be registered for a verifying context, so that every registered
+	/// layout is accepted by the verifier.
+	pub const SUPPORTED: &'static [Self] = &[Self::V1];
+
+	/// Returns `true` if `self` is one of the [`SUPPORTED`](Self::SUPPORTED) layouts.
+	#[inline]
+	pub fn is_supported(&self) -> bool {
+		Self::SUPPORTED.contains(self)
+	}
+}
+
+impl Default for TransferPostLayout {
+	#[inline]
+	fn default() -> Self {
+		Self::V1
+	}
+}
+
+/// Receiver Post Version 2
+#[derive(Clone, Debug, Decode, Encode, Eq, Hash, PartialEq, TypeInfo)]
+pub struct ReceiverPostV2 {
+	/// Unspent Transaction Output
+	pub utxo: [u8; 32],
+
+	/// Ephemeral Public Key
+	pub ephemeral_public_key: [u8; 32],
+
+	/// Ciphertext
+	pub ciphertext: Vec<u8>,
+}
+
+impl From<config::ReceiverPost> for ReceiverPostV2 {
+	#[inline]
+	fn from(post: config::ReceiverPost) -> Self {
+		Self {
+			utxo: encode(post.utxo),
+			ephemeral_public_key: encode(post.encrypted_note.ephemeral_public_key),
+			ciphertext: post.encrypted_note.ciphertext.encode(),
+		}
+	}
+}
+
+impl TryFrom<ReceiverPostV2> for config::ReceiverPost {
+	type Error = ();
+
+	#[inline]
+	fn try_from(post: ReceiverPostV2) -> Result<Self, Self::Error> {
+		Ok(Self {
+			utxo: decode(post.utxo)?,
+			encrypted_note: config::EncryptedNote {
+				ephemeral_public_key: decode(post.ephemeral_public_key)?,
+				ciphertext: decode_exact(&post.ciphertext)?,
+			},
+		})
+	}
+}
+
+/// Transfer Post Version 2
+///
+/// Carries proofs and ciphertexts of any length, which must match the [`TransferPostLayout`]
+/// registered for the shape of the post.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq, TypeInfo)]
+pub struct TransferPostV2 {
+	/// Asset Id
+	pub asset_id: Option<AssetId>,
+
+	/// Sources
+	pub sources: Vec<Balance>,
+
+	/// Sender Posts
+	pub sender_posts: Vec<SenderPost>,
+
+	/// Receiver Posts
+	pub receiver_posts: Vec<ReceiverPostV2>,
+
+	/// Sinks
+	pub sinks: Vec<Balance>,
+
+	/// Validity Proof
+	pub validity_proof: Vec<u8>,
+}
+
+impl TransferPostV2 {
+	/// Returns `true` if the proof and the ciphertexts of `self` have the lengths of `layout`.
+	#[inline]
+	pub fn has_layout(&self, layout: &TransferPostLayout) -> bool {
+		let has_length = |bytes: &[u8], length: u32| u32::try_from(bytes.len()) == Ok(length);
+		has_length(&self.validity_proof, layout.proof_length)
+			&& self
+				.receiver_posts
+				.iter()
+				.all(|post| has_length(&post.ciphertext, layout.ciphertext_length))
+	}
+}
+
+impl From<config::TransferPost> for TransferPostV2 {
+	#[inline]
+	fn from(post: config::TransferPost) -> Self {
+		Self {
+			asset_id: post.asset_id.map(|id| id.0),
+			sources: post.sources.into_iter().map(|s| s.0).collect(),
+			sender_posts: post.sender_posts.into_iter().map(Into::into).collect(),
+			receiver_posts: post.receiver_posts.into_iter().map(Into::into).collect(),
+			sinks: post.sinks.into_iter().map(|s| s.0).collect(),
+			validity_proof: post.validity_proof.encode(),
+		}
+	}
+}
+
+impl TryFrom<TransferPostV2> for config::TransferPost {
+	type Error = ();
+
+	#[inline]
+	fn try_from(post: TransferPostV2) -> Result<Self, Self::Error> {
+		Ok(Self {
+			asset_id: post.asset_id.map(asset::AssetId),
+			sources: post.sources.into_iter().map(asset::AssetValue).collect(),
+			sender_posts: post
+				.sender_posts
+				.into_iter()
+				.map(TryInto::try_into)
+				.collect::<Result<_, _>>()?,
+			receiver_posts: post
+				.receiver_posts
+				.into_iter()
+				.map(TryInto::try_into)
+				.collect::<Result<_, _>>()?,
+			sinks: post.sinks.into_iter().map(asset::AssetValue).collect(),
+			validity_proof: decode_exact(&post.validity_proof)?,
+		})
+	}
+}
+
+/// Versioned Transfer Post
+///
+/// Posts accepted by [`transfer`](crate::Pallet::transfer). The existing calls keep taking the
+/// version 1 [`TransferPost`] directly, so their encoding is unchanged.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq, TypeInfo)]
+pub enum VersionedTransferPost {
+	/// Version 1 Transfer Post
+	V1(TransferPost),
+
+	/// Version 2 Transfer Post
+	V2(TransferPostV2),
+}
+
+impl VersionedTransferPost {
+	/// Versions of the transfer posts accepted by the pallet
+	pub const SUPPORTED_VERSIONS: &'static [u8] = &[1, 2];
+
+	/// Returns the version of `self`.
+	#[inline]
+	pub fn version(&self) -> u8 {
+		match self {
+			Self::V1(_) => 1,
+			Self::V2(_) => 2,
+		}
+	}
+
+	/// Returns the sources of `self`.
+	#[inline]
+	pub fn sources(&self) -> &[Balance] {
+		match self {
+			Self::V1(post) => &post.sources,
+			Self::V2(post) => &post.sources,
+		}
+	}
+
+	/// Returns the sender posts of `self`.
+	#[inline]
+	pub fn sender_posts(&self) -> &[SenderPost] {
+		match self {
+			Self::V1(post) => &post.sender_posts,
+			Self::V2(post) => &post.sender_posts,
+		}
+	}
+
+	/// Returns the sinks of `self`.
+	#[inline]
+	pub fn sinks(&self) -> &[Balance] {
+		match self {
+			Self::V1(post) => &post.sinks,
+			Self::V2(post) => &post.sinks,
+		}
+	}
+
+	/// Returns the UTXOs of the receiver posts of `self`.
+	#[inline]
+	pub fn utxos(&self) -> Vec<&Utxo> {
+		match self {
+			Self::V1(post) => post.receiver_posts.iter().map(|post| &post.utxo).collect(),
+			Self::V2(post) => post.receiver_posts.iter().map(|post| &post.utxo).collect(),
+		}
+	}
+
+	/// Returns the asset id of `self`, if it has public participants.
+	#[inline]
+	pub fn asset_id(&self) -> Option<AssetId> {
+		match self {
+			Self::V1(post) => post.asset_id,
+			Self::V2(post) => post.asset_id,
+		}
+	}
+
+	/// Returns `true` if the proof and the ciphertexts of `self` have the lengths of `layout`.
+	#[inline]
+	pub fn has_layout(&self, layout: &TransferPostLayout) -> bool {
+		match self {
+			Self::V1(_) => *layout == TransferPostLayout::V1,
+			Self::V2(post) => post.has_layout(layout),
+		}
+	}
+}
+
+impl From<TransferPost> for VersionedTransferPost {
+	#[inline]
+	fn from(post: TransferPost) -> Self {
+		Self::V1(post)
+	}
+}
+
+impl From<TransferPostV2> for VersionedTransferPost {
+	#[inline]
+	fn from(post: TransferPostV2) -> Self {
+		Self::V2(post)
+	}
+}
+
+impl TryFrom<VersionedTransferPost> for config::TransferPost {
+	type Error = ();
+
+	#[inline]
+	fn try_from(post: VersionedTransferPost) -> Result<Self, Self::Error> {
+		match post {
+			VersionedTransferPost::V1(post) => post.try_into(),
+			VersionedTransferPost::V2(post) => post.try_into(),
+		}
+	}
+}
+
+/// Precomputed Coins
+///
+/// Every entry is valid against an empty ledger, after posting the mints it spends, if any.
+#[derive(Clone, Debug, Decode, Default, Encode, Eq, PartialEq, TypeInfo)]
+pub struct PrecomputedCoins {
+	/// Mint Posts
+	pub mints: Vec<TransferPost>,
+
+	/// Private Transfer Posts with the Mint Posts they Spend
+	pub private_transfers: Vec<([TransferPost; 2], TransferPost)>,
+
+	/// Reclaim Posts with the Mint Posts they Spend
+	pub reclaims: Vec<([TransferPost; 2], TransferPost)>,
+}
+
+/// Versioned Precomputed Coins
+///
+/// Format of the files written by the `precompute_coins` binary.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq, TypeInfo)]
+pub enum VersionedPrecomputedCoins {
+	/// Version 1
+	#[codec(index = 1)]
+	V1(PrecomputedCoins),
+}
+
+impl VersionedPrecomputedCoins {
+	/// Decodes the precomputed coins in `bytes`, returning the latest version of their format.
+	#[inline]
+	pub fn decode_latest(mut bytes: &[u8]) -> Option<PrecomputedCoins> {
+		match Self::decode(&mut bytes).ok()? {
+			Self::V1(coins) => Some(coins),
+		}
+	}
+}
+
+impl From<PrecomputedCoins> for VersionedPrecomputedCoins {
+	#[inline]
+	fn from(coins: PrecomputedCoins) -> Self {
+		Self::V1(coins)
+	}
+}
+
+/// Transfer Shape Kind
+///
+/// Identifies the circuit used to verify a transfer of the corresponding shape.
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Decode,
+	Encode,
+	Eq,
+	Hash,
+	MaxEncodedLen,
+	Ord,
+	PartialEq,
+	PartialOrd,
+	TypeInfo,
+)]
+pub enum TransferShapeKind {
+	/// Mint Transfer Shape
+	Mint,
+
+	/// Private Transfer Shape
+	PrivateTransfer,
+
+	/// Reclaim Transfer Shape
+	Reclaim,
+
+	/// Custom Transfer Shape
+	///
+	/// Shapes outside of the canonical ones are only accepted once they have been registered with
+	/// [`register_transfer_shape`](crate::Pallet::register_transfer_shape).
+	Custom {
+		/// Number of Sources
+		sources: u8,
+
+		/// Number of Senders
+		senders: u8,
+
+		/// Number of Receivers
+		receivers: u8,
+
+		/// Number of Sinks
+		sinks: u8,
+	},
+}
+
+impl TransferShapeKind {
+	/// Selects the transfer shape with the given number of participants, preferring the canonical
+	/// shapes over custom ones.
+	///
+	/// Custom shapes have at most one source or one sink, never both, and must carry an asset id
+	/// exactly when they have public participants.
+	#[inline]
+	pub fn select(
+		has_asset: bool,
+		sources: usize,
+		senders: usize,
+		receivers: usize,
+		sinks: usize,
+	) -> Option<Self> {
+		if let Some(shape) = TransferShape::select(has_asset, sources, senders, receivers, sinks) {
+			return Some(shape.into());
+		}
+		if !matches!((sources, sinks), (0, 0) | (1, 0) | (0, 1))
+			|| has_asset != (sources + sinks > 0)
+			|| senders + receivers == 0
+		{
+			return None;
+		}
+		Some(Self::Custom {
+			sources: sources as u8,
+			senders: senders.try_into().ok()?,
+			receivers: receivers.try_into().ok()?,
+			sinks: sinks as u8,
+		})
+	}
+
+	/// Selects the transfer shape of `post`.
+	#[inline]
+	pub fn of(post: &TransferPost) -> Option<Self> {
+		Self::select(
+			post.asset_id.is_some(),
+			post.sources.len(),
+			post.sender_posts.len(),
+			post.receiver_posts.len(),
+			post.sinks.len(),
+		)
+	}
+
+	/// Selects the transfer shape of the versioned `post`.
+	#[inline]
+	pub fn of_versioned(post: &VersionedTransferPost) -> Option<Self> {
+		Self::select(
+			post.asset_id().is_some(),
+			post.sources().len(),
+			post.sender_posts().len(),
+			post.utxos().len(),
+			post.sinks().len(),
+		)
+	}
+
+	/// Returns `true` if `self` is a custom shape which [`select`](Self::select) can return.
+	#[inline]
+	pub fn is_valid_custom(&self) -> bool {
+		match *self {
+			Self::Custom {
+				sources,
+				senders,
+				receivers,
+				sinks,
+			} => {
+				Self::select(
+					sources > 0 || sinks > 0,
+					sources.into(),
+					senders.into(),
+					receivers.into(),
+					sinks.into(),
+				) == Some(*self)
+			}
+			_ => false,
+		}
+	}
+
+	/// Returns `true` if `self` has no public participants.
+	#[inline]
+	pub fn is_private(&self) -> bool {
+		match *self {
+			Self::PrivateTransfer => true,
+			Self::Custom { sources, sinks, .. } => sources == 0 && sinks == 0,
+			_ => false,
+		}
+	}
+}
+
+impl From<TransferShape> for TransferShapeKind {
+	#[inline]
+	fn from(shape: TransferShape) -> Self {
+		match shape {
+			TransferShape::Mint => Self::Mint,
+			TransferShape::PrivateTransfer => Self::PrivateTransfer,
+			TransferShape::Reclaim => Self::Reclaim,
+		}
+	}
+}
+
+/// Versioned Parameters
+///
+/// Serialized protocol parameters, such as a verifying context or the UTXO accumulator model,
+/// tagged with a version number which must increase on every update.
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Decode, Default, Encode, Eq, Hash, PartialEq, TypeInfo)]
+pub struct VersionedParameters {
+	/// Parameters Version
+	pub version: u32,
+
+	/// Serialized Parameters
+	pub bytes: Vec<u8>,
+}
+
+impl VersionedParameters {
+	/// Builds a new [`VersionedParameters`] from `version` and `bytes`.
+	#[inline]
+	pub fn new(version: u32, bytes: Vec<u8>) -> Self {
+		Self { version, bytes }
+	}
+}
+
+/// Shielding Policy
+///
+/// Governs how an asset may be converted into private assets with [`to_private`]. Assets without a
+/// shielding policy can be shielded without restriction.
+///
+/// [`to_private`]: crate::Pallet::to_private
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(Clone, Copy, Debug, Decode, Encode, Eq, Hash, MaxEncodedLen, PartialEq, TypeInfo)]
+pub struct ShieldingPolicy {
+	/// Shieldable Flag
+	///
+	/// If `false`, the asset cannot be converted into a private asset.
+	pub is_shieldable: bool,
+
+	/// Minimum Value of a Mint
+	pub min_mint_value: Balance,
+
+	/// Maximum Shielded Supply
+	///
+	/// The shielded supply of an asset is the value of that asset deposited into the pallet account
+	/// by posts and not yet withdrawn, so public assets sent to the pallet account in any other way
+	/// are not counted.
+	pub max_shielded_supply: Balance,
+}
+
+impl ShieldingPolicy {
+	/// Builds a new [`ShieldingPolicy`] from `is_shieldable`, `min_mint_value` and
+	/// `max_shielded_supply`.
+	#[inline]
+	pub fn new(is_shieldable: bool, min_mint_value: Balance, max_shielded_supply: Balance) -> Self {
+		Self {
+			is_shieldable,
+			min_mint_value,
+			max_shielded_supply,
+		}
+	}
+
+	/// Builds a [`ShieldingPolicy`] which allows shielding any amount of an asset.
+	#[inline]
+	pub fn unrestricted() -> Self {
+		Self::new(true, 0, Balance::MAX)
+	}
+}
+
+/// Ledger Operation
+///
+/// Kind of post which can be frozen with [`set_frozen`].
+///
+/// [`set_frozen`]: crate::Pallet::set_frozen
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(Clone, Copy, Debug, Decode, Encode, Eq, Hash, MaxEncodedLen, PartialEq, TypeInfo)]
+pub enum LedgerOperation {
+	/// Conversion of Public Assets into Private Ones
+	Mint,
+
+	/// Transfer of Private Assets
+	///
+	/// Private transfers do not reveal their asset, so they can only be frozen for all assets.
+	PrivateTransfer,
+
+	/// Conversion of Private Assets into Public Ones
+	Reclaim,
+}
+
 /// Leaf Digest Type
 pub type LeafDigest = merkle_tree::LeafDigest<config::MerkleTreeConfiguration>;
 
@@ -301,3 +913,124 @@ pub struct UtxoMerkleTreePath {
 	/// Current Path
 	pub current_path: CurrentPath,
 }
+
+/// Shard Replay Cursor
+///
+/// Progress of the V0->V1 migration, which replays the insertions into every shard to recover the
+/// order of its Utxo accumulator outputs.
+#[derive(Clone, Debug, Decode, Default, Encode, Eq, PartialEq, TypeInfo)]
+pub struct ShardReplayCursor {
+	/// Index of the Shard being Replayed
+	pub shard_index: u8,
+
+	/// Index of the Next Leaf to Replay
+	pub leaf_index: u64,
+
+	/// Merkle Tree Path after the Replayed Leaves
+	pub tree: UtxoMerkleTreePath,
+
+	/// Most Recent Utxo Accumulator Outputs of the Replayed Leaves, from Oldest to Newest
+	pub outputs: Vec<[u8; 32]>,
+}
+
+/// Receiver Chunk Data Type
+pub type ReceiverChunk = Vec<(Utxo, EncryptedNote)>;
+
+/// Sender Chunk Data Type
+pub type SenderChunk = Vec<VoidNumber>;
+
+/// Ledger Checkpoint
+///
+/// Marks how much of the ledger a client has already synchronized: the number of UTXOs it has
+/// seen in each shard and the number of void numbers it has seen in insertion order.
+#[cfg_attr(
+	feature = "serde",
+	derive(Deserialize, Serialize),
+	serde(deny_unknown_fields, rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, Decode, Default, Encode, Eq, Hash, PartialEq, TypeInfo)]
+pub struct Checkpoint {
+	/// Receiver Index
+	///
+	/// Entry `i` is the next leaf index to pull from shard `i`. Missing entries are treated as
+	/// zero so an empty vector is the checkpoint of an empty ledger.
+	pub receiver_index: Vec<u64>,
+
+	/// Sender Index
+	pub sender_index: u64,
+}
+
+impl Checkpoint {
+	/// Builds a new [`Checkpoint`] from `receiver_index` and `sender_index`.
+	#[inline]
+	pub fn new(receiver_index: Vec<u64>, sender_index: u64) -> Self {
+		Self {
+			receiver_index,
+			sender_index,
+		}
+	}
+
+	/// Returns the next leaf index to pull from the shard at `shard_index`.
+	#[inline]
+	pub fn receiver_index(&self, shard_index: u8) -> u64 {
+		self.receiver_index
+			.get(shard_index as usize)
+			.copied()
+			.unwrap_or_default()
+	}
+}
+
+/// Ledger Pull Response
+#[cfg_attr(
+	feature = "serde",
+	derive(Deserialize, Serialize),
+	serde(deny_unknown_fields, rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, Decode, Default, Encode, Eq, Hash, PartialEq, TypeInfo)]
+pub struct PullResponse {
+	/// Pull Continuation Flag
+	///
+	/// The `should_continue` flag is set to `true` if the client should request more data from
+	/// the ledger to finish the pull.
+	pub should_continue: bool,
+
+	/// Ledger Receiver Chunk
+	///
+	/// Receivers are ordered by shard and then by leaf index within each shard.
+	pub receivers: ReceiverChunk,
+
+	/// Ledger Sender Chunk
+	///
+	/// Void numbers are ordered by insertion.
+	pub senders: SenderChunk,
+}
+
+/// UTXO Membership Path
+///
+/// Full path from a UTXO to the root of its shard tree, as served by the node-side shard index.
+/// Digests are SCALE encoded so that they can be decoded into the Merkle tree digests of
+/// [`config::MerkleTreeConfiguration`].
+#[cfg_attr(
+	feature = "serde",
+	derive(Deserialize, Serialize),
+	serde(deny_unknown_fields, rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, Decode, Default, Encode, Eq, Hash, PartialEq, TypeInfo)]
+pub struct MembershipPath {
+	/// Shard Index
+	pub shard_index: u8,
+
+	/// Leaf Index
+	pub leaf_index: u64,
+
+	/// Shard Tree Root
+	pub root: [u8; 32],
+
+	/// Sibling Digest
+	pub sibling_digest: [u8; 32],
+
+	/// Inner Path
+	///
+	/// Inner digests ordered from the bottom of the tree to the top.
+	pub inner_path: Vec<[u8; 32]>,
+}
@@ -36,14 +36,60 @@ pub trait WeightInfo {
 	/// Returns the [`Weight`] of the [`Pallet::to_private`] extrinsic.
 	fn to_private() -> Weight;
 
+	/// Returns the [`Weight`] of the [`Pallet::to_private_from`] extrinsic.
+	fn to_private_from() -> Weight;
+
 	/// Returns the [`Weight`] of the [`Pallet::to_public`] extrinsic.
 	fn to_public() -> Weight;
 
+	/// Returns the [`Weight`] of the [`Pallet::to_public_for`] extrinsic.
+	fn to_public_for() -> Weight;
+
+	/// Returns the [`Weight`] of the [`Pallet::to_public_with_fee`] extrinsic.
+	fn to_public_with_fee() -> Weight;
+
 	/// Returns the [`Weight`] of the [`Pallet::private_transfer`] extrinsic.
 	fn private_transfer() -> Weight;
 
+	/// Returns the [`Weight`] of the [`Pallet::to_public_remote`] extrinsic.
+	fn to_public_remote() -> Weight;
+
+	/// Returns the [`Weight`] of the [`Pallet::private_transfer_unsigned`] extrinsic.
+	fn private_transfer_unsigned() -> Weight;
+
+	/// Returns the [`Weight`] of the [`Pallet::transfer`] extrinsic for a version 2 private
+	/// transfer.
+	fn transfer() -> Weight;
+
 	/// Returns the [`Weight`] of the [`Pallet::public_transfer`] extrinsic.
 	fn public_transfer() -> Weight;
+
+	/// Returns the [`Weight`] of the [`Pallet::set_verifying_context`] extrinsic.
+	fn set_verifying_context() -> Weight;
+
+	/// Returns the [`Weight`] of the [`Pallet::register_transfer_shape`] extrinsic.
+	fn register_transfer_shape() -> Weight;
+
+	/// Returns the [`Weight`] of the [`Pallet::deregister_transfer_shape`] extrinsic.
+	fn deregister_transfer_shape() -> Weight;
+
+	/// Returns the [`Weight`] of the [`Pallet::set_utxo_accumulator_model`] extrinsic.
+	fn set_utxo_accumulator_model() -> Weight;
+
+	/// Returns the [`Weight`] of the [`Pallet::set_shielding_policy`] extrinsic.
+	fn set_shielding_policy() -> Weight;
+
+	/// Returns the [`Weight`] of the [`Pallet::approve_to_private`] extrinsic.
+	fn approve_to_private() -> Weight;
+
+	/// Returns the [`Weight`] of the [`Pallet::set_frozen`] extrinsic.
+	fn set_frozen() -> Weight;
+
+	/// Returns the [`Weight`] of replaying `l` shard leaves in [`Pallet::replay_shards`].
+	fn replay_shards(l: u32) -> Weight;
+
+	/// Returns the [`Weight`] of registering a fixed number of UTXOs into `s` distinct shards.
+	fn register_utxos(s: u32) -> Weight;
 }
 
 /// Concrete Weight Functions
@@ -70,7 +116,7 @@ where
 	/// Storage: MantaPay UtxoSetOutputs (r:2 w:1)
 	/// Storage: MantaPay VoidNumberSet (r:2 w:2)
 	/// Storage: MantaPay UtxoSet (r:1 w:1)
-	/// Storage: MantaPay VoidNumberSetSize (r:1 w:1)
+	/// Storage: MantaPay CounterForVoidNumberSetInsertionOrder (r:1 w:1)
 	/// Storage: MantaPay ShardTrees (r:1 w:1)
 	/// Storage: MantaPay Balances (r:1 w:1)
 	/// Storage: MantaPay VoidNumberSetInsertionOrder (r:0 w:2)
@@ -82,11 +128,39 @@ where
 			.saturating_add(T::DbWeight::get().writes(12 as Weight))
 	}
 
+	/// Weight of `to_private` plus the approval update
+	///
+	/// ```text
+	/// Storage: MantaPay ToPrivateApprovals (r:1 w:1)
+	/// ```
+	fn to_private_from() -> Weight {
+		Self::to_private()
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	/// Weight of `to_public`, which only differs in the sink account
+	fn to_public_for() -> Weight {
+		Self::to_public()
+	}
+
+	/// Weight of `to_public` plus the fee payment
+	///
+	/// ```text
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Storage: Assets Account (r:2 w:2)
+	/// ```
+	fn to_public_with_fee() -> Weight {
+		Self::to_public()
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+
 	/// ```text
 	/// Storage: MantaPay UtxoSetOutputs (r:2 w:2)
 	/// Storage: MantaPay VoidNumberSet (r:2 w:2)
 	/// Storage: MantaPay UtxoSet (r:2 w:2)
-	/// Storage: MantaPay VoidNumberSetSize (r:1 w:1)
+	/// Storage: MantaPay CounterForVoidNumberSetInsertionOrder (r:1 w:1)
 	/// Storage: MantaPay ShardTrees (r:2 w:2)
 	/// Storage: MantaPay VoidNumberSetInsertionOrder (r:0 w:2)
 	/// Storage: MantaPay Shards (r:0 w:2)
@@ -97,8 +171,145 @@ where
 			.saturating_add(T::DbWeight::get().writes(13 as Weight))
 	}
 
+	/// Weight of `to_public`, which sends the assets out of the pallet account instead
+	fn to_public_remote() -> Weight {
+		Self::to_public()
+	}
+
+	/// Weight of `private_transfer`, which only differs in the origin
+	fn private_transfer_unsigned() -> Weight {
+		Self::private_transfer()
+	}
+
+	/// Weight of `private_transfer` plus the layout check
+	///
+	/// ```text
+	/// Storage: MantaPay TransferPostLayouts (r:1 w:0)
+	/// ```
+	fn transfer() -> Weight {
+		Self::private_transfer().saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+
 	/// FIXME: PLACEHOLDER WEIGHT
 	fn public_transfer() -> Weight {
 		(100_000_000_000 as Weight)
 	}
+
+	/// FIXME: PLACEHOLDER WEIGHT
+	///
+	/// ```text
+	/// Storage: MantaPay CustomTransferShapes (r:1 w:0)
+	/// Storage: MantaPay VerifyingContexts (r:1 w:1)
+	/// Storage: MantaPay TransferPostLayouts (r:0 w:1)
+	/// ```
+	fn set_verifying_context() -> Weight {
+		(100_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+
+	/// FIXME: PLACEHOLDER WEIGHT
+	///
+	/// ```text
+	/// Storage: MantaPay VerifyingContexts (r:1 w:1)
+	/// Storage: MantaPay CustomTransferShapes (r:0 w:1)
+	/// Storage: MantaPay TransferPostLayouts (r:0 w:1)
+	/// ```
+	fn register_transfer_shape() -> Weight {
+		(100_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+
+	/// FIXME: PLACEHOLDER WEIGHT
+	///
+	/// ```text
+	/// Storage: MantaPay CustomTransferShapes (r:1 w:1)
+	/// Storage: MantaPay VerifyingContexts (r:0 w:1)
+	/// Storage: MantaPay TransferPostLayouts (r:0 w:1)
+	/// ```
+	fn deregister_transfer_shape() -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+
+	/// FIXME: PLACEHOLDER WEIGHT
+	///
+	/// ```text
+	/// Storage: MantaPay UtxoAccumulatorModelParameters (r:1 w:1)
+	/// Storage: MantaPay ShardTrees (r:1 w:0)
+	/// ```
+	fn set_utxo_accumulator_model() -> Weight {
+		(100_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	/// FIXME: PLACEHOLDER WEIGHT
+	///
+	/// ```text
+	/// Storage: MantaPay ShieldingPolicies (r:0 w:1)
+	/// ```
+	fn set_shielding_policy() -> Weight {
+		(1_000_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	/// FIXME: PLACEHOLDER WEIGHT
+	///
+	/// ```text
+	/// Storage: MantaPay ToPrivateApprovals (r:0 w:1)
+	/// ```
+	fn approve_to_private() -> Weight {
+		(1_000_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	/// FIXME: PLACEHOLDER WEIGHT
+	///
+	/// ```text
+	/// Storage: MantaPay FrozenOperations (r:0 w:1)
+	/// ```
+	fn set_frozen() -> Weight {
+		(1_000_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	/// FIXME: PLACEHOLDER WEIGHT
+	///
+	/// ```text
+	/// Storage: MantaPay ShardReplay (r:1 w:1)
+	/// Storage: MantaPay UtxoAccumulatorModelParameters (r:1 w:0)
+	/// Storage: MantaPay Shards (r:1 w:0)
+	/// Storage: MantaPay UtxoAccumulatorOutputs (r:1 w:0)
+	/// Storage: MantaPay ExpiredUtxoAccumulatorOutputs (r:0 w:1)
+	/// ```
+	fn replay_shards(l: u32) -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add((1_000_000_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(l as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(l as Weight)))
+	}
+
+	/// FIXME: PLACEHOLDER WEIGHT
+	///
+	/// ```text
+	/// Storage: MantaPay UtxoAccumulatorModelParameters (r:1 w:0)
+	/// Storage: MantaPay ShardTrees (r:1 w:1)
+	/// Storage: MantaPay ShardUtilization (r:1 w:1)
+	/// Storage: MantaPay UtxoAccumulatorOutputRingHead (r:1 w:1)
+	/// Storage: MantaPay UtxoAccumulatorOutputRing (r:1 w:1)
+	/// Storage: MantaPay ExpiredUtxoAccumulatorOutputs (r:0 w:1)
+	/// Storage: MantaPay UtxoAccumulatorOutputs (r:0 w:1)
+	/// Storage: MantaPay UtxoSet (r:0 w:1)
+	/// Storage: MantaPay Shards (r:0 w:1)
+	/// ```
+	fn register_utxos(s: u32) -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add((1_000_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(T::DbWeight::get().writes(16 as Weight))
+			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(s as Weight)))
+	}
 }
@@ -344,6 +344,9 @@ where
 	/// Checks if an asset id is valid and returning and [`Error`](FungibleLedgerError) otherwise.
 	fn ensure_valid(asset_id: AssetId) -> Result<(), FungibleLedgerError>;
 
+	/// Returns the balance of `account` in the given `asset_id`.
+	fn balance(asset_id: AssetId, account: &C::AccountId) -> Result<Balance, FungibleLedgerError>;
+
 	/// Check whether `account` can increase its balance by `amount` in the given `asset_id`.
 	fn can_deposit(
 		asset_id: AssetId,
@@ -399,6 +402,16 @@ where
 		}
 	}
 
+	#[inline]
+	fn balance(asset_id: AssetId, account: &C::AccountId) -> Result<Balance, FungibleLedgerError> {
+		Self::ensure_valid(asset_id)?;
+		Ok(if asset_id == A::NativeAssetId::get() {
+			<Native as FungibleInspect<C::AccountId>>::balance(account)
+		} else {
+			<NonNative as FungiblesInspect<C::AccountId>>::balance(asset_id, account)
+		})
+	}
+
 	#[inline]
 	fn can_deposit(
 		asset_id: AssetId,
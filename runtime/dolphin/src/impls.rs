@@ -14,8 +14,34 @@
 // You should have received a copy of the GNU General Public License
 // along with Manta.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{Authorship, Balances, NegativeImbalance, Treasury};
-use frame_support::traits::{Currency, Imbalance, OnUnbalanced};
+use crate::{
+	AccountId, Authorship, Balances, BaseXcmWeight, Call, CurrencyId, MantaAssetConfig, MantaPay,
+	NegativeImbalance, Origin, Runtime, Treasury, XTokens,
+};
+use codec::Decode;
+use core::marker::PhantomData;
+use frame_support::{
+	dispatch::DispatchResult,
+	storage::{with_transaction, TransactionOutcome},
+	weights::Weight,
+};
+use frame_support::traits::{
+	Currency, ExistenceRequirement, Get, Imbalance, OnUnbalanced, PalletInfoAccess,
+	WithdrawReasons,
+};
+use manta_primitives::{
+	assets::{AssetConfig, FungibleLedger, FungibleLedgerError},
+	types::{AssetId, Balance},
+};
+use pallet_manta_pay::{
+	types::{Asset, TransferPost},
+	OnShieldedFee, RemoteTransfer, WeightInfo,
+};
+use sp_std::boxed::Box;
+use xcm::{latest::prelude::*, VersionedMultiLocation};
+use xcm_executor::traits::{
+	Error as MatchError, MatchesFungible, MatchesFungibles, TransactAsset, WeightBounds,
+};
 
 pub struct Author;
 impl OnUnbalanced<NegativeImbalance> for Author {
@@ -40,3 +66,197 @@ impl OnUnbalanced<NegativeImbalance> for DealWithFees {
 		}
 	}
 }
+
+/// Routes fees paid out of MantaPay shielded balances like regular transaction fees: the native
+/// asset goes through [`DealWithFees`] and other assets are sent to the treasury.
+pub struct ShieldedFees;
+impl OnShieldedFee<AccountId> for ShieldedFees {
+	fn on_shielded_fee(payer: &AccountId, asset: Asset) -> Result<(), FungibleLedgerError> {
+		type Ledger = <MantaAssetConfig as AssetConfig<Runtime>>::FungibleLedger;
+		if asset.id == <MantaAssetConfig as AssetConfig<Runtime>>::NativeAssetId::get() {
+			Ledger::can_withdraw(asset.id, payer, asset.value)?;
+			let fee = Balances::withdraw(
+				payer,
+				asset.value,
+				WithdrawReasons::FEE,
+				ExistenceRequirement::KeepAlive,
+			)
+			.map_err(|_| FungibleLedgerError::NoFunds)?;
+			DealWithFees::on_unbalanceds(Some(fee).into_iter());
+			Ok(())
+		} else {
+			Ledger::transfer(asset.id, payer, &Treasury::account_id(), asset.value)
+		}
+	}
+}
+
+/// Shields fungible assets received over XCM into MantaPay.
+///
+/// Assets deposited to `MultiLocation { parents: 0, interior: X2(PalletInstance(MantaPay),
+/// GeneralKey(post)) }` are shielded with the SCALE-encoded mint `post`, so they never land in a
+/// public account. The post is checked against the asset first, then the asset is deposited into
+/// the MantaPay pallet account with `Transactor` and the post is submitted in the same storage
+/// transaction, so the deposit is reverted if the post fails. Deposits to any other location are
+/// left to the next transactor.
+pub struct MantaPayShieldingTransactor<Transactor, Matcher>(PhantomData<(Transactor, Matcher)>);
+impl<Transactor, Matcher> TransactAsset for MantaPayShieldingTransactor<Transactor, Matcher>
+where
+	Transactor: TransactAsset,
+	Matcher: MatchesFungibles<AssetId, Balance>,
+{
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation) -> XcmResult {
+		let post = match who {
+			MultiLocation {
+				parents: 0,
+				interior: X2(PalletInstance(index), GeneralKey(key)),
+			} if *index as usize == MantaPay::index() => TransferPost::decode(&mut key.as_slice())
+				.map_err(|_| XcmError::FailedToTransactAsset("Invalid MantaPay post"))?,
+			_ => return Err(XcmError::AssetNotFound),
+		};
+		let (asset_id, value) =
+			Matcher::matches_fungibles(what).map_err(|_| XcmError::AssetNotFound)?;
+		let asset = Asset::new(asset_id, value);
+		MantaPay::check_to_private_from_deposit(&post, asset)
+			.map_err(|err| XcmError::FailedToTransactAsset(err.into()))?;
+		with_transaction(|| {
+			let result = Transactor::deposit_asset(
+				what,
+				&MultiLocation::new(
+					0,
+					X1(AccountId32 {
+						network: NetworkId::Any,
+						id: MantaPay::account_id().into(),
+					}),
+				),
+			)
+			.and_then(|_| {
+				MantaPay::to_private_from_deposit(post, asset)
+					.map_err(|err| XcmError::FailedToTransactAsset(err.into()))
+			});
+			match result {
+				Ok(_) => TransactionOutcome::Commit(Ok(())),
+				Err(err) => TransactionOutcome::Rollback(Err(err)),
+			}
+		})
+	}
+}
+
+/// Matches the native asset with `Matcher`, as the native asset id of MantaPay.
+pub struct NativeAssetMatcher<Matcher>(PhantomData<Matcher>);
+impl<Matcher> MatchesFungibles<AssetId, Balance> for NativeAssetMatcher<Matcher>
+where
+	Matcher: MatchesFungible<Balance>,
+{
+	fn matches_fungibles(a: &MultiAsset) -> Result<(AssetId, Balance), MatchError> {
+		Matcher::matches_fungible(a)
+			.map(|value| {
+				(
+					<MantaAssetConfig as AssetConfig<Runtime>>::NativeAssetId::get(),
+					value,
+				)
+			})
+			.ok_or(MatchError::AssetNotFound)
+	}
+}
+
+/// Weighs XCM messages with `Weigher`, adding the weight of a MantaPay mint for each asset which
+/// can be deposited to the MantaPay location by [`MantaPayShieldingTransactor`].
+pub struct MantaPayShieldingWeigher<Weigher>(PhantomData<Weigher>);
+impl<Weigher> MantaPayShieldingWeigher<Weigher> {
+	/// Returns the weight of the MantaPay mints posted by `instruction`.
+	fn shielding_weight<Call>(instruction: &Instruction<Call>) -> Result<Weight, ()> {
+		match instruction {
+			DepositAsset {
+				max_assets,
+				beneficiary:
+					MultiLocation {
+						parents: 0,
+						interior: X2(PalletInstance(index), GeneralKey(_)),
+					},
+				..
+			} if *index as usize == MantaPay::index() => {
+				<Runtime as pallet_manta_pay::Config>::WeightInfo::to_private()
+					.checked_mul(*max_assets as Weight)
+					.ok_or(())
+			}
+			SetErrorHandler(xcm) | SetAppendix(xcm) => {
+				xcm.0.iter().try_fold(0, |weight: Weight, instruction| {
+					weight
+						.checked_add(Self::shielding_weight(instruction)?)
+						.ok_or(())
+				})
+			}
+			_ => Ok(0),
+		}
+	}
+}
+impl<Weigher, Call> WeightBounds<Call> for MantaPayShieldingWeigher<Weigher>
+where
+	Weigher: WeightBounds<Call>,
+{
+	fn weight(message: &mut Xcm<Call>) -> Result<Weight, ()> {
+		let weight = Weigher::weight(message)?;
+		message.0.iter().try_fold(weight, |weight, instruction| {
+			weight
+				.checked_add(Self::shielding_weight(instruction)?)
+				.ok_or(())
+		})
+	}
+
+	fn instr_weight(instruction: &Instruction<Call>) -> Result<Weight, ()> {
+		Weigher::instr_weight(instruction)?
+			.checked_add(Self::shielding_weight(instruction)?)
+			.ok_or(())
+	}
+}
+
+/// Sends assets reclaimed by MantaPay to other chains with `orml_xtokens`, straight out of the
+/// MantaPay pallet account.
+pub struct MantaPayRemoteTransfer;
+impl RemoteTransfer<AccountId> for MantaPayRemoteTransfer {
+	type Destination = VersionedMultiLocation;
+
+	fn transfer(
+		source: AccountId,
+		asset: Asset,
+		destination: Self::Destination,
+		destination_weight: Weight,
+	) -> DispatchResult {
+		XTokens::transfer(
+			Origin::signed(source),
+			CurrencyId::MantaCurrency(asset.id),
+			asset.value,
+			Box::new(destination),
+			destination_weight,
+		)
+	}
+
+	fn weight() -> Weight {
+		// This is how `orml_xtokens` weighs `transfer`, for the heaviest transfer kind, which
+		// withdraws the asset and initiates a reserve withdrawal.
+		let mut message = Xcm::<Call>(vec![
+			WithdrawAsset(MultiAssets::new()),
+			InitiateReserveWithdraw {
+				assets: Wild(All),
+				reserve: MultiLocation::parent(),
+				xcm: Xcm(vec![]),
+			},
+		]);
+		<Runtime as orml_xtokens::Config>::Weigher::weight(&mut message)
+			.map_or(Weight::max_value(), |weight| {
+				BaseXcmWeight::get().saturating_add(weight)
+			})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_destination() -> Self::Destination {
+		MultiLocation::new(
+			1,
+			X1(AccountId32 {
+				network: Any,
+				id: [0; 32],
+			}),
+		)
+		.into()
+	}
+}
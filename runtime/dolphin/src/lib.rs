@@ -30,12 +30,14 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT},
-	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, Perbill, Permill,
+	transaction_validity::{
+		TransactionLongevity, TransactionPriority, TransactionSource, TransactionValidity,
+	},
+	ApplyExtrinsicResult, Perbill, Percent, Permill,
 };
 
 use sp_core::u32_trait::{_1, _2, _3, _4, _5};
-use sp_std::{cmp::Ordering, prelude::*};
+use sp_std::{cmp::Ordering, iter, prelude::*};
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
@@ -46,7 +48,7 @@ use frame_support::{
 	parameter_types,
 	traits::{
 		ConstU16, ConstU32, ConstU8, Contains, Currency, EnsureOneOf, Everything, Nothing,
-		PrivilegeCmp,
+		OnRuntimeUpgrade, PrivilegeCmp,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, WEIGHT_PER_SECOND},
@@ -130,10 +132,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("dolphin"),
 	impl_name: create_runtime_str!("dolphin"),
 	authoring_version: 1,
-	spec_version: 3150,
+	spec_version: 3200,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 0,
 };
 
@@ -717,6 +719,21 @@ pub type FungiblesTransactor = FungiblesAdapter<
 	(),
 >;
 
+/// Transactor shielding the native currency and currency in pallet-assets into MantaPay, see
+/// [`impls::MantaPayShieldingTransactor`].
+pub type MantaPayShieldingTransactor = impls::MantaPayShieldingTransactor<
+	(LocalAssetTransactor, FungiblesTransactor),
+	(
+		impls::NativeAssetMatcher<IsNativeConcrete<SelfReserve>>,
+		ConvertedConcreteAssetId<
+			AssetId,
+			Balance,
+			AssetIdLocationConvert<AssetLocation, AssetManager>,
+			JustTry,
+		>,
+	),
+>;
+
 match_type! {
 	pub type ParentOrParentsExecutivePlurality: impl Contains<MultiLocation> = {
 		MultiLocation { parents: 1, interior: Here } |
@@ -771,14 +788,17 @@ impl Config for XcmExecutorConfig {
 	// Defines how to Withdraw and Deposit instruction work
 	// Under the hood, substrate framework will do pattern matching in macro,
 	// as a result, the order of the following tuple matters.
-	type AssetTransactor = (LocalAssetTransactor, FungiblesTransactor);
+	// MantaPay shielding comes first, so deposits to the MantaPay location are never handled
+	// by the other transactors.
+	type AssetTransactor = (MantaPayShieldingTransactor, LocalAssetTransactor, FungiblesTransactor);
 	type OriginConverter = XcmOriginToCallOrigin;
 	// Combinations of (Location, Asset) pairs which we trust as reserves.
 	type IsReserve = MultiNativeAsset;
 	type IsTeleporter = ();
 	type LocationInverter = LocationInverter<Ancestry>;
 	type Barrier = Barrier;
-	type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+	type Weigher =
+		impls::MantaPayShieldingWeigher<FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>>;
 	// Trader is the means to purchasing weight credit for XCM execution.
 	// We define two traders:
 	// The first one will charge parachain's native currency, who's `MultiLocation`
@@ -1069,6 +1089,10 @@ impl pallet_asset_manager::Config for Runtime {
 
 parameter_types! {
 	pub const MantaPayPalletId: PalletId = MANTA_PAY_PALLET_ID;
+	pub const MantaPayUnsignedPriority: TransactionPriority = TransactionPriority::MIN;
+	pub const MantaPayUnsignedLongevity: TransactionLongevity = 16;
+	pub const MantaPayShardUtilizationThreshold: Percent = Percent::from_percent(80);
+	pub const MantaPayUtxoAccumulatorOutputWindow: u32 = 64;
 }
 
 impl pallet_manta_pay::Config for Runtime {
@@ -1076,6 +1100,14 @@ impl pallet_manta_pay::Config for Runtime {
 	type WeightInfo = weights::pallet_manta_pay::SubstrateWeight<Runtime>;
 	type AssetConfig = MantaAssetConfig;
 	type PalletId = MantaPayPalletId;
+	type ParametersOrigin = EnsureRoot<AccountId>;
+	type FreezeOrigin = EnsureRoot<AccountId>;
+	type UtxoAccumulatorOutputWindow = MantaPayUtxoAccumulatorOutputWindow;
+	type ShardUtilizationThreshold = MantaPayShardUtilizationThreshold;
+	type RemoteTransfer = impls::MantaPayRemoteTransfer;
+	type UnsignedPriority = MantaPayUnsignedPriority;
+	type UnsignedLongevity = MantaPayUnsignedLongevity;
+	type OnShieldedFee = impls::ShieldedFees;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -1135,7 +1167,7 @@ construct_runtime!(
 		// Asset and Private Payment
 		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>} = 45,
 		AssetManager: pallet_asset_manager::{Pallet, Call, Storage, Config<T>, Event<T>} = 46,
-		MantaPay: pallet_manta_pay::{Pallet, Call, Storage, Event<T>} = 47,
+		MantaPay: pallet_manta_pay::{Pallet, Call, Storage, Event<T>, Config, ValidateUnsigned} = 47,
 	}
 );
 
@@ -1156,6 +1188,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_manta_pay::extension::CheckVoidNumbers<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -1168,8 +1201,78 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsReversedWithSystemFirst,
+	(
+		MantaPayMigrationV1,
+		MantaPayMigrationV2,
+		MantaPayMigrationV3,
+		MantaPayMigrationV4,
+	),
 >;
 
+pub struct MantaPayMigrationV1;
+impl OnRuntimeUpgrade for MantaPayMigrationV1 {
+	fn on_runtime_upgrade() -> Weight {
+		MantaPay::migrate_v0_to_v1()
+	}
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		MantaPay::pre_migrate_v0_to_v1()
+	}
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		MantaPay::post_migrate_v0_to_v1()
+	}
+}
+
+pub struct MantaPayMigrationV2;
+impl OnRuntimeUpgrade for MantaPayMigrationV2 {
+	fn on_runtime_upgrade() -> Weight {
+		MantaPay::migrate_v1_to_v2()
+	}
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		MantaPay::pre_migrate_v1_to_v2()
+	}
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		MantaPay::post_migrate_v1_to_v2()
+	}
+}
+
+pub struct MantaPayMigrationV3;
+impl OnRuntimeUpgrade for MantaPayMigrationV3 {
+	fn on_runtime_upgrade() -> Weight {
+		MantaPay::migrate_v2_to_v3()
+	}
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		MantaPay::pre_migrate_v2_to_v3()
+	}
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		MantaPay::post_migrate_v2_to_v3()
+	}
+}
+
+pub struct MantaPayMigrationV4;
+impl OnRuntimeUpgrade for MantaPayMigrationV4 {
+	fn on_runtime_upgrade() -> Weight {
+		// Every asset registered with the asset manager, from the native one to the last one
+		let asset_ids = iter::once(NativeAssetId::get())
+			.chain(StartNonNativeAssetId::get()..pallet_asset_manager::NextAssetId::<Runtime>::get());
+		MantaPay::migrate_v3_to_v4(asset_ids)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		MantaPay::pre_migrate_v3_to_v4()
+	}
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		MantaPay::post_migrate_v3_to_v4()
+	}
+}
+
 impl_runtime_apis! {
 	impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
 		fn slot_duration() -> sp_consensus_aura::SlotDuration {
@@ -1271,6 +1374,30 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_manta_pay::runtime::MantaPayPullApi<Block> for Runtime {
+		fn pull_ledger_diff(
+			checkpoint: pallet_manta_pay::types::Checkpoint,
+		) -> pallet_manta_pay::types::PullResponse {
+			MantaPay::pull_ledger_diff(checkpoint)
+		}
+	}
+
+	impl pallet_manta_pay::runtime::MantaPayTransferPostApi<Block> for Runtime {
+		fn transfer_post_layout(
+			shape: pallet_manta_pay::types::TransferShapeKind,
+		) -> pallet_manta_pay::types::TransferPostLayout {
+			MantaPay::transfer_post_layout(shape)
+		}
+
+		fn supported_transfer_post_versions() -> Vec<u8> {
+			MantaPay::supported_transfer_post_versions()
+		}
+
+		fn supported_transfer_post_layouts() -> Vec<pallet_manta_pay::types::TransferPostLayout> {
+			MantaPay::supported_transfer_post_layouts()
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
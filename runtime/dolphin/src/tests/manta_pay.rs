@@ -0,0 +1,121 @@
+// Copyright 2020-2022 Manta Network.
+// This file is part of Manta.
+//
+// Manta is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Manta is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Manta.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for Shielding into MantaPay over XCM
+
+use crate::{
+	AssetManager, Assets, Call, Event, MantaPay, Origin, Runtime, System, XcmExecutorConfig,
+};
+use codec::Encode;
+use frame_support::{
+	assert_ok,
+	traits::{GenesisBuild, PalletInfoAccess},
+	weights::Weight,
+};
+use manta_primitives::assets::{AssetLocation, AssetRegistrarMetadata};
+use pallet_manta_pay::{
+	types::{TransferPost, VersionedPrecomputedCoins},
+	WeightInfo,
+};
+use xcm::{latest::prelude::*, VersionedMultiLocation};
+use xcm_executor::{traits::WeightBounds, Config, XcmExecutor};
+
+/// Precomputed Coins generated by `pallets/manta-pay/src/bin/precompute_coins.rs`
+const PRECOMPUTED_COINS: &[u8] =
+	include_bytes!("../../../../pallets/manta-pay/src/benchmark/precomputed_coins.scale");
+
+/// Builds the externalities with the relay chain asset registered as the asset of the
+/// precomputed coins.
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default()
+		.build_storage::<Runtime>()
+		.unwrap();
+	pallet_asset_manager::GenesisConfig::<Runtime>::default()
+		.assimilate_storage(&mut storage)
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(AssetManager::register_asset(
+			Origin::root(),
+			AssetLocation(VersionedMultiLocation::V1(MultiLocation::parent())),
+			AssetRegistrarMetadata {
+				min_balance: 1,
+				is_sufficient: true,
+				..Default::default()
+			},
+		));
+	});
+	ext
+}
+
+/// Returns the relay chain deposit of `value` shielded with `post`, and its weight.
+fn shielding_message(value: u128, post: &TransferPost) -> (Xcm<Call>, Weight) {
+	let mut message = Xcm(vec![
+		ReserveAssetDeposited(vec![(Parent, value).into()].into()),
+		ClearOrigin,
+		DepositAsset {
+			assets: Wild(All),
+			max_assets: 1,
+			beneficiary: MultiLocation::new(
+				0,
+				X2(
+					PalletInstance(MantaPay::index() as u8),
+					GeneralKey(post.encode()),
+				),
+			),
+		},
+	]);
+	let weight = <XcmExecutorConfig as Config>::Weigher::weight(&mut message).unwrap();
+	(message, weight)
+}
+
+/// Tests that assets deposited over XCM to the MantaPay location are shielded by the mint post
+/// of the location, and that the deposit is reverted if the post fails.
+#[test]
+fn shielding_deposit_should_work() {
+	new_test_ext().execute_with(|| {
+		let coins = VersionedPrecomputedCoins::decode_latest(PRECOMPUTED_COINS)
+			.expect("Unable to decode the precomputed coins.");
+		let post = coins.mints[0].clone();
+		let (asset_id, value) = (post.asset_id.unwrap(), post.sources[0]);
+		let (message, weight) = shielding_message(value, &post);
+		assert!(weight >= <Runtime as pallet_manta_pay::Config>::WeightInfo::to_private());
+		assert_ok!(XcmExecutor::<XcmExecutorConfig>::execute_xcm_in_credit(
+			Parent, message, weight, weight
+		)
+		.ensure_complete());
+		assert_eq!(Assets::balance(asset_id, MantaPay::account_id()), value);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			Event::MantaPay(pallet_manta_pay::Event::ToPrivate { .. })
+		)));
+		let (message, weight) = shielding_message(value, &post);
+		assert!(XcmExecutor::<XcmExecutorConfig>::execute_xcm_in_credit(
+			Parent, message, weight, weight
+		)
+		.ensure_complete()
+		.is_err());
+		assert_eq!(Assets::balance(asset_id, MantaPay::account_id()), value);
+		let (message, weight) = shielding_message(value - 1, &post);
+		assert!(XcmExecutor::<XcmExecutorConfig>::execute_xcm_in_credit(
+			Parent, message, weight, weight
+		)
+		.ensure_complete()
+		.is_err());
+		assert_eq!(Assets::balance(asset_id, MantaPay::account_id()), value);
+	});
+}
@@ -16,6 +16,7 @@
 
 //! Simulation Tests for XCM
 
+mod manta_pay;
 mod xcm_mock;
 
 use codec::Encode;
@@ -45,8 +45,23 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn to_private() -> Weight;
 	fn private_transfer() -> Weight;
+	fn to_private_from() -> Weight;
 	fn to_public() -> Weight;
+	fn to_public_for() -> Weight;
+	fn to_public_with_fee() -> Weight;
+	fn to_public_remote() -> Weight;
+	fn private_transfer_unsigned() -> Weight;
+	fn transfer() -> Weight;
 	fn public_transfer() -> Weight;
+	fn set_verifying_context() -> Weight;
+	fn register_transfer_shape() -> Weight;
+	fn deregister_transfer_shape() -> Weight;
+	fn set_utxo_accumulator_model() -> Weight;
+	fn set_shielding_policy() -> Weight;
+	fn approve_to_private() -> Weight;
+	fn set_frozen() -> Weight;
+	fn replay_shards(l: u32, ) -> Weight;
+	fn register_utxos(s: u32, ) -> Weight;
 }
 
 /// Weights for pallet_manta_pay using the Substrate node and recommended hardware.
@@ -55,7 +70,7 @@ impl<T: frame_system::Config> pallet_manta_pay::WeightInfo for SubstrateWeight<T
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: MantaPay UtxoSet (r:1 w:1)
-	// Storage: MantaPay VoidNumberSetSize (r:1 w:0)
+	// Storage: MantaPay CounterForVoidNumberSetInsertionOrder (r:1 w:0)
 	// Storage: MantaPay ShardTrees (r:1 w:1)
 	// Storage: MantaPay UtxoAccumulatorOutputs (r:0 w:1)
 	// Storage: MantaPay Shards (r:0 w:1)
@@ -67,7 +82,7 @@ impl<T: frame_system::Config> pallet_manta_pay::WeightInfo for SubstrateWeight<T
 	// Storage: MantaPay UtxoAccumulatorOutputs (r:2 w:2)
 	// Storage: MantaPay VoidNumberSet (r:2 w:2)
 	// Storage: MantaPay UtxoSet (r:2 w:2)
-	// Storage: MantaPay VoidNumberSetSize (r:1 w:1)
+	// Storage: MantaPay CounterForVoidNumberSetInsertionOrder (r:1 w:1)
 	// Storage: MantaPay ShardTrees (r:2 w:2)
 	// Storage: MantaPay VoidNumberSetInsertionOrder (r:0 w:2)
 	// Storage: MantaPay Shards (r:0 w:2)
@@ -81,7 +96,7 @@ impl<T: frame_system::Config> pallet_manta_pay::WeightInfo for SubstrateWeight<T
 	// Storage: MantaPay UtxoAccumulatorOutputs (r:2 w:1)
 	// Storage: MantaPay VoidNumberSet (r:2 w:2)
 	// Storage: MantaPay UtxoSet (r:1 w:1)
-	// Storage: MantaPay VoidNumberSetSize (r:1 w:1)
+	// Storage: MantaPay CounterForVoidNumberSetInsertionOrder (r:1 w:1)
 	// Storage: MantaPay ShardTrees (r:1 w:1)
 	// Storage: MantaPay VoidNumberSetInsertionOrder (r:0 w:2)
 	// Storage: MantaPay Shards (r:0 w:1)
@@ -90,13 +105,131 @@ impl<T: frame_system::Config> pallet_manta_pay::WeightInfo for SubstrateWeight<T
 			.saturating_add(T::DbWeight::get().reads(10 as Weight))
 			.saturating_add(T::DbWeight::get().writes(12 as Weight))
 	}
+	// Weight of `to_private` plus the approval update
+	// Storage: MantaPay ToPrivateApprovals (r:1 w:1)
+	fn to_private_from() -> Weight {
+		Self::to_private()
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Weight of `to_public`, which only differs in the sink account
+	fn to_public_for() -> Weight {
+		Self::to_public()
+	}
+	// Weight of `to_public` plus the fee payment
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn to_public_with_fee() -> Weight {
+		Self::to_public()
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Weight of `to_public`, which sends the assets out of the pallet account instead
+	fn to_public_remote() -> Weight {
+		Self::to_public()
+	}
+	// Weight of `private_transfer`, which only differs in the origin
+	fn private_transfer_unsigned() -> Weight {
+		Self::private_transfer()
+	}
+	// Weight of `private_transfer` plus the layout check
+	// Storage: MantaPay TransferPostLayouts (r:1 w:0)
+	fn transfer() -> Weight {
+		Self::private_transfer()
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	fn public_transfer() -> Weight {
 		(34_745_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
-	}	
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay CustomTransferShapes (r:1 w:0)
+	// Storage: MantaPay VerifyingContexts (r:1 w:1)
+	// Storage: MantaPay TransferPostLayouts (r:0 w:1)
+	fn set_verifying_context() -> Weight {
+		(100_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay VerifyingContexts (r:1 w:1)
+	// Storage: MantaPay CustomTransferShapes (r:0 w:1)
+	// Storage: MantaPay TransferPostLayouts (r:0 w:1)
+	fn register_transfer_shape() -> Weight {
+		(100_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay CustomTransferShapes (r:1 w:1)
+	// Storage: MantaPay VerifyingContexts (r:0 w:1)
+	// Storage: MantaPay TransferPostLayouts (r:0 w:1)
+	fn deregister_transfer_shape() -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay UtxoAccumulatorModelParameters (r:1 w:1)
+	// Storage: MantaPay ShardTrees (r:1 w:0)
+	fn set_utxo_accumulator_model() -> Weight {
+		(100_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay ShieldingPolicies (r:0 w:1)
+	fn set_shielding_policy() -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay ToPrivateApprovals (r:0 w:1)
+	fn approve_to_private() -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay FrozenOperations (r:0 w:1)
+	fn set_frozen() -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay ShardReplay (r:1 w:1)
+	// Storage: MantaPay UtxoAccumulatorModelParameters (r:1 w:0)
+	// Storage: MantaPay Shards (r:1 w:0)
+	// Storage: MantaPay UtxoAccumulatorOutputs (r:1 w:0)
+	// Storage: MantaPay ExpiredUtxoAccumulatorOutputs (r:0 w:1)
+	fn replay_shards(l: u32, ) -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add((1_000_000_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(l as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(l as Weight)))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay UtxoAccumulatorModelParameters (r:1 w:0)
+	// Storage: MantaPay ShardTrees (r:1 w:1)
+	// Storage: MantaPay ShardUtilization (r:1 w:1)
+	// Storage: MantaPay UtxoAccumulatorOutputRingHead (r:1 w:1)
+	// Storage: MantaPay UtxoAccumulatorOutputRing (r:1 w:1)
+	// Storage: MantaPay ExpiredUtxoAccumulatorOutputs (r:0 w:1)
+	// Storage: MantaPay UtxoAccumulatorOutputs (r:0 w:1)
+	// Storage: MantaPay UtxoSet (r:0 w:1)
+	// Storage: MantaPay Shards (r:0 w:1)
+	fn register_utxos(s: u32, ) -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add((1_000_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(T::DbWeight::get().writes(16 as Weight))
+			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(s as Weight)))
+	}
 }
 
 // For backwards compatibility and tests
@@ -104,7 +237,7 @@ impl WeightInfo for () {
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: MantaPay UtxoSet (r:1 w:1)
-	// Storage: MantaPay VoidNumberSetSize (r:1 w:0)
+	// Storage: MantaPay CounterForVoidNumberSetInsertionOrder (r:1 w:0)
 	// Storage: MantaPay ShardTrees (r:1 w:1)
 	// Storage: MantaPay UtxoAccumulatorOutputs (r:0 w:1)
 	// Storage: MantaPay Shards (r:0 w:1)
@@ -116,7 +249,7 @@ impl WeightInfo for () {
 	// Storage: MantaPay UtxoAccumulatorOutputs (r:2 w:2)
 	// Storage: MantaPay VoidNumberSet (r:2 w:2)
 	// Storage: MantaPay UtxoSet (r:2 w:2)
-	// Storage: MantaPay VoidNumberSetSize (r:1 w:1)
+	// Storage: MantaPay CounterForVoidNumberSetInsertionOrder (r:1 w:1)
 	// Storage: MantaPay ShardTrees (r:2 w:2)
 	// Storage: MantaPay VoidNumberSetInsertionOrder (r:0 w:2)
 	// Storage: MantaPay Shards (r:0 w:2)
@@ -130,7 +263,7 @@ impl WeightInfo for () {
 	// Storage: MantaPay UtxoAccumulatorOutputs (r:2 w:1)
 	// Storage: MantaPay VoidNumberSet (r:2 w:2)
 	// Storage: MantaPay UtxoSet (r:1 w:1)
-	// Storage: MantaPay VoidNumberSetSize (r:1 w:1)
+	// Storage: MantaPay CounterForVoidNumberSetInsertionOrder (r:1 w:1)
 	// Storage: MantaPay ShardTrees (r:1 w:1)
 	// Storage: MantaPay VoidNumberSetInsertionOrder (r:0 w:2)
 	// Storage: MantaPay Shards (r:0 w:1)
@@ -139,6 +272,39 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(12 as Weight))
 	}
+	// Weight of `to_private` plus the approval update
+	// Storage: MantaPay ToPrivateApprovals (r:1 w:1)
+	fn to_private_from() -> Weight {
+		Self::to_private()
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Weight of `to_public`, which only differs in the sink account
+	fn to_public_for() -> Weight {
+		Self::to_public()
+	}
+	// Weight of `to_public` plus the fee payment
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn to_public_with_fee() -> Weight {
+		Self::to_public()
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Weight of `to_public`, which sends the assets out of the pallet account instead
+	fn to_public_remote() -> Weight {
+		Self::to_public()
+	}
+	// Weight of `private_transfer`, which only differs in the origin
+	fn private_transfer_unsigned() -> Weight {
+		Self::private_transfer()
+	}
+	// Weight of `private_transfer` plus the layout check
+	// Storage: MantaPay TransferPostLayouts (r:1 w:0)
+	fn transfer() -> Weight {
+		Self::private_transfer()
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	fn public_transfer() -> Weight {
@@ -146,4 +312,89 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay CustomTransferShapes (r:1 w:0)
+	// Storage: MantaPay VerifyingContexts (r:1 w:1)
+	// Storage: MantaPay TransferPostLayouts (r:0 w:1)
+	fn set_verifying_context() -> Weight {
+		(100_000_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay VerifyingContexts (r:1 w:1)
+	// Storage: MantaPay CustomTransferShapes (r:0 w:1)
+	// Storage: MantaPay TransferPostLayouts (r:0 w:1)
+	fn register_transfer_shape() -> Weight {
+		(100_000_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay CustomTransferShapes (r:1 w:1)
+	// Storage: MantaPay VerifyingContexts (r:0 w:1)
+	// Storage: MantaPay TransferPostLayouts (r:0 w:1)
+	fn deregister_transfer_shape() -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay UtxoAccumulatorModelParameters (r:1 w:1)
+	// Storage: MantaPay ShardTrees (r:1 w:0)
+	fn set_utxo_accumulator_model() -> Weight {
+		(100_000_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay ShieldingPolicies (r:0 w:1)
+	fn set_shielding_policy() -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay ToPrivateApprovals (r:0 w:1)
+	fn approve_to_private() -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay FrozenOperations (r:0 w:1)
+	fn set_frozen() -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay ShardReplay (r:1 w:1)
+	// Storage: MantaPay UtxoAccumulatorModelParameters (r:1 w:0)
+	// Storage: MantaPay Shards (r:1 w:0)
+	// Storage: MantaPay UtxoAccumulatorOutputs (r:1 w:0)
+	// Storage: MantaPay ExpiredUtxoAccumulatorOutputs (r:0 w:1)
+	fn replay_shards(l: u32, ) -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add((1_000_000_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(l as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(l as Weight)))
+	}
+	// FIXME: PLACEHOLDER WEIGHT
+	// Storage: MantaPay UtxoAccumulatorModelParameters (r:1 w:0)
+	// Storage: MantaPay ShardTrees (r:1 w:1)
+	// Storage: MantaPay ShardUtilization (r:1 w:1)
+	// Storage: MantaPay UtxoAccumulatorOutputRingHead (r:1 w:1)
+	// Storage: MantaPay UtxoAccumulatorOutputRing (r:1 w:1)
+	// Storage: MantaPay ExpiredUtxoAccumulatorOutputs (r:0 w:1)
+	// Storage: MantaPay UtxoAccumulatorOutputs (r:0 w:1)
+	// Storage: MantaPay UtxoSet (r:0 w:1)
+	// Storage: MantaPay Shards (r:0 w:1)
+	fn register_utxos(s: u32, ) -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add((1_000_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(16 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(s as Weight)))
+	}
 }